@@ -1,13 +1,246 @@
-#[derive(Clone, Debug, PartialEq)]
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use crate::BytePos;
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Ast {
-    pub top: Mod,
+    pub uses: Vec<Use>,
+    pub consts: Vec<Const>,
+    pub enums: Vec<Enum>,
+    pub structs: Vec<Struct>,
+    pub interfaces: Vec<Interface>,
+    pub fns: Vec<Function>,
+    pub aliases: Vec<Alias>,
+    pub mods: Vec<Mod>,
+}
+
+impl Ast {
+    /// The module marked `top`, if there is one.
+    pub fn top(&self) -> Option<&Mod> {
+        self.mods.iter().find(|m| m.top)
+    }
+
+    /// Looks up a module by name.
+    pub fn module(&self, name: &str) -> Option<&Mod> {
+        self.mods.iter().find(|m| m.name == name)
+    }
+
+    /// Looks up an enum by name.
+    pub fn enumeration(&self, name: &str) -> Option<&Enum> {
+        self.enums.iter().find(|e| e.name == name)
+    }
+
+    /// Looks up a struct by name.
+    pub fn structure(&self, name: &str) -> Option<&Struct> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    /// Looks up an interface by name.
+    pub fn interface(&self, name: &str) -> Option<&Interface> {
+        self.interfaces.iter().find(|i| i.name == name)
+    }
+
+    /// Looks up a type alias by name.
+    pub fn alias(&self, name: &str) -> Option<&Alias> {
+        self.aliases.iter().find(|a| a.name == name)
+    }
+
+    /// Looks up a function by name.
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.fns.iter().find(|f| f.name == name)
+    }
+
+    /// Whether an item of any kind is declared under a name.
+    pub fn declares(&self, name: &str) -> bool {
+        self.module(name).is_some()
+            || self.consts.iter().any(|c| c.name == name)
+            || self.enumeration(name).is_some()
+            || self.structure(name).is_some()
+            || self.interface(name).is_some()
+            || self.function(name).is_some()
+            || self.alias(name).is_some()
+    }
+
+    /// Adds the items of another file to this one.
+    pub fn merge(&mut self, other: Ast) {
+        self.uses.extend(other.uses);
+        self.consts.extend(other.consts);
+        self.enums.extend(other.enums);
+        self.structs.extend(other.structs);
+        self.interfaces.extend(other.interfaces);
+        self.fns.extend(other.fns);
+        self.aliases.extend(other.aliases);
+        self.mods.extend(other.mods);
+    }
+}
+
+/// Brings an item declared in another file into scope, written as the path
+/// of the file relative to a search path directory followed by the item,
+/// such as `use lib::fifo::Fifo;` for `Fifo` within `lib/fifo.si`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Use {
+    pub file: Vec<String>,
+    pub item: String,
+    pub pos: BytePos,
+}
+
+/// A bundle of named fields, which is flattened into one signal per field
+/// once lowered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Struct {
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+/// Another name for a type, declared at file scope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alias {
+    pub name: String,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub ty: Type,
+    pub pos: BytePos,
+}
+
+/// A group of fields that is connected as one, each field with a direction of
+/// its own. The directions are those seen by a module driving the interface
+/// through an `out` port.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interface {
+    pub name: String,
+    pub fields: Vec<Port>,
+}
+
+/// A pure combinational function, inlined wherever it is called. Its body
+/// declares local values ahead of the expression giving its result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Field>,
+    pub ret: Type,
+    pub body: Vec<Stmt>,
+    pub result: Expr,
+    pub pos: BytePos,
+}
+
+/// An attribute, written `#[name]` or `#[name(value)]` ahead of an item.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attr {
+    pub name: String,
+    pub value: Option<String>,
+    pub pos: BytePos,
+}
+
+/// Attributes understood by synthesis tools, which are passed on to the
+/// Verilog declarations of the items they are found on.
+pub const SYNTHESIS_ATTRS: &[&str] = &["keep", "ram_style", "mark_debug", "async_reg"];
+
+impl Attr {
+    /// Whether this is one of the [`SYNTHESIS_ATTRS`].
+    pub fn is_synthesis(&self) -> bool {
+        SYNTHESIS_ATTRS.contains(&self.name.as_str())
+    }
+}
+
+/// An enumerated type. Its variants are encoded as selected by an
+/// `encoding` attribute, which defaults to binary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Enum {
+    pub name: String,
+    pub attrs: Vec<Attr>,
+    pub variants: Vec<String>,
+}
+
+/// The ways the variants of an enum can be encoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    /// Variants are numbered in declaration order.
+    Binary,
+    /// Each variant sets a single bit of its own.
+    OneHot,
+    /// Variants are numbered in declaration order, in Gray code, so that
+    /// neighbouring variants differ by a single bit.
+    Gray,
+}
+
+impl Enum {
+    /// The encoding selected by the `encoding` attribute, or `None` if it
+    /// names an unknown one.
+    pub fn encoding(&self) -> Option<Encoding> {
+        let attr = match self.attrs.iter().find(|a| a.name == "encoding") {
+            Some(attr) => attr,
+            None => return Some(Encoding::Binary),
+        };
+        match attr.value.as_deref() {
+            Some("binary") => Some(Encoding::Binary),
+            Some("one_hot") => Some(Encoding::OneHot),
+            Some("gray") => Some(Encoding::Gray),
+            _ => None,
+        }
+    }
+
+    /// The type of values of this enum.
+    pub fn ty(&self) -> Type {
+        Type::Enum {
+            name: self.name.clone(),
+            width: self.width(),
+        }
+    }
+
+    /// Number of bits needed to encode every variant.
+    pub fn width(&self) -> usize {
+        match self.encoding() {
+            Some(Encoding::OneHot) => self.variants.len().max(1),
+            _ => {
+                let max = BigUint::from(self.variants.len().max(2) - 1);
+                max.bits()
+            }
+        }
+    }
+
+    /// The encoded value of a variant.
+    pub fn value(&self, variant: &str) -> Option<BigUint> {
+        let idx = self.variants.iter().position(|v| v == variant)?;
+        match self.encoding() {
+            Some(Encoding::OneHot) => Some(BigUint::from(1u32) << idx),
+            Some(Encoding::Gray) => Some(BigUint::from(idx ^ (idx >> 1))),
+            _ => Some(BigUint::from(idx)),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Mod {
     pub name: String,
+    pub attrs: Vec<Attr>,
+    pub top: bool,
+    pub params: Vec<Param>,
     pub ports: Vec<Port>,
     pub stmts: Vec<Stmt>,
+    pub pos: BytePos,
+}
+
+/// A compile-time constant. Constants without a type are unbounded unsigned
+/// integers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Const {
+    pub name: String,
+    pub ty: Option<Type>,
+    pub ex: Expr,
+    pub pos: BytePos,
+}
+
+/// A module parameter, which can be overridden by each instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub default: Expr,
+    pub pos: BytePos,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -15,32 +248,481 @@ pub struct Port {
     pub dir: Dir,
     pub name: String,
     pub ty: Type,
+    /// Only ports of modules have attributes, unlike fields of interfaces.
+    pub attrs: Vec<Attr>,
+    pub pos: BytePos,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Dir {
     Input,
     Output,
+    InOut,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl Dir {
+    /// The opposite direction. Bidirectional ports stay bidirectional.
+    pub fn flipped(&self) -> Dir {
+        match self {
+            Dir::Input => Dir::Output,
+            Dir::Output => Dir::Input,
+            Dir::InOut => Dir::InOut,
+        }
+    }
+
+    /// The direction of a field of an interface reached through a port or
+    /// field of this direction. Going in flips the directions within, while
+    /// anything reached through a bidirectional port is bidirectional.
+    pub fn compose(&self, inner: &Dir) -> Dir {
+        match self {
+            Dir::Input => inner.flipped(),
+            Dir::Output => inner.clone(),
+            Dir::InOut => Dir::InOut,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub enum Type {
     Bit,
+    Bits {
+        width: usize,
+    },
+    UInt {
+        width: usize,
+    },
+    SInt {
+        width: usize,
+    },
+    Clock,
+    Reset,
+    AsyncReset,
+    /// A vector sized by an expression over module parameters, which is
+    /// replaced by one of the sized vector types during elaboration.
+    Generic {
+        kind: Vector,
+        width: Box<Expr>,
+        pos: BytePos,
+    },
+    /// A type referred to by name, which is resolved during elaboration.
+    Named {
+        name: String,
+    },
+    Enum {
+        name: String,
+        width: usize,
+    },
+    /// A struct, along with the resolved types of its fields.
+    Struct {
+        name: String,
+        fields: Vec<Field>,
+    },
+    /// An interface, along with the directions and resolved types of its
+    /// fields.
+    Interface {
+        name: String,
+        fields: Vec<Port>,
+    },
+    /// An interface with every field direction reversed, which is resolved
+    /// during elaboration.
+    Flipped {
+        ty: Box<Type>,
+    },
+    /// A fixed number of elements, found in memories and constants.
+    Array {
+        elem: Box<Type>,
+        len: usize,
+    },
+    /// An array whose length is given by an expression over module
+    /// parameters, which is replaced by a sized array during elaboration.
+    GenericArray {
+        elem: Box<Type>,
+        len: Box<Expr>,
+        pos: BytePos,
+    },
+    /// A type alias along with the type it stands for. Aliases behave as that
+    /// type everywhere, but keep their name to show in errors.
+    Alias {
+        name: String,
+        ty: Box<Type>,
+    },
+}
+
+/// Aliases are the same type as the one they stand for.
+impl PartialEq for Type {
+    fn eq(&self, other: &Type) -> bool {
+        match (self.unaliased(), other.unaliased()) {
+            (Type::Bit, Type::Bit)
+            | (Type::Clock, Type::Clock)
+            | (Type::Reset, Type::Reset)
+            | (Type::AsyncReset, Type::AsyncReset) => true,
+            (Type::Bits { width: l }, Type::Bits { width: r })
+            | (Type::UInt { width: l }, Type::UInt { width: r })
+            | (Type::SInt { width: l }, Type::SInt { width: r }) => l == r,
+            (
+                Type::Generic {
+                    kind: lkind,
+                    width: lwidth,
+                    pos: lpos,
+                },
+                Type::Generic {
+                    kind: rkind,
+                    width: rwidth,
+                    pos: rpos,
+                },
+            ) => lkind == rkind && lwidth == rwidth && lpos == rpos,
+            (Type::Named { name: l }, Type::Named { name: r }) => l == r,
+            (
+                Type::Enum {
+                    name: lname,
+                    width: lwidth,
+                },
+                Type::Enum {
+                    name: rname,
+                    width: rwidth,
+                },
+            ) => lname == rname && lwidth == rwidth,
+            (
+                Type::Struct {
+                    name: lname,
+                    fields: lfields,
+                },
+                Type::Struct {
+                    name: rname,
+                    fields: rfields,
+                },
+            ) => lname == rname && lfields == rfields,
+            (
+                Type::Interface {
+                    name: lname,
+                    fields: lfields,
+                },
+                Type::Interface {
+                    name: rname,
+                    fields: rfields,
+                },
+            ) => lname == rname && lfields == rfields,
+            (Type::Flipped { ty: l }, Type::Flipped { ty: r }) => l == r,
+            (
+                Type::Array {
+                    elem: lelem,
+                    len: llen,
+                },
+                Type::Array {
+                    elem: relem,
+                    len: rlen,
+                },
+            ) => lelem == relem && llen == rlen,
+            (
+                Type::GenericArray {
+                    elem: lelem,
+                    len: llen,
+                    pos: lpos,
+                },
+                Type::GenericArray {
+                    elem: relem,
+                    len: rlen,
+                    pos: rpos,
+                },
+            ) => lelem == relem && llen == rlen && lpos == rpos,
+            _ => false,
+        }
+    }
+}
+
+/// The kinds of vector types.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Vector {
+    Bits,
+    UInt,
+    SInt,
+}
+
+impl Vector {
+    /// The vector type of this kind with a known width.
+    pub fn sized(&self, width: usize) -> Type {
+        match self {
+            Vector::Bits => Type::Bits { width },
+            Vector::UInt => Type::UInt { width },
+            Vector::SInt => Type::SInt { width },
+        }
+    }
+
+    /// The vector type of this kind with a width given by an expression,
+    /// which is sized right away if it's a literal. Literal widths must be
+    /// positive.
+    pub fn of(&self, width: Expr, pos: BytePos) -> Option<Type> {
+        match width {
+            Expr::Litrl { val, .. } => match val.to_usize() {
+                Some(width) if width > 0 => Some(self.sized(width)),
+                _ => None,
+            },
+            width => Some(Type::Generic {
+                kind: self.clone(),
+                width: Box::new(width),
+                pos,
+            }),
+        }
+    }
+}
+
+impl Type {
+    /// The array of `len` elements, which is sized right away if the length
+    /// is a literal. Literal lengths must be positive.
+    pub fn array(elem: Type, len: Expr, pos: BytePos) -> Option<Type> {
+        let elem = Box::new(elem);
+        match len {
+            Expr::Litrl { val, .. } => match val.to_usize() {
+                Some(len) if len > 0 => Some(Type::Array { elem, len }),
+                _ => None,
+            },
+            len => Some(Type::GenericArray {
+                elem,
+                len: Box::new(len),
+                pos,
+            }),
+        }
+    }
+
+    /// The type an alias stands for, or the type itself for any other type.
+    pub fn unaliased(&self) -> &Type {
+        match self {
+            Type::Alias { ty, .. } => ty.unaliased(),
+            ty => ty,
+        }
+    }
+
+    /// Number of bits needed to hold a value of this type.
+    ///
+    /// Panics for generic and named types, which have no width until
+    /// elaborated.
+    pub fn width(&self) -> usize {
+        match self.unaliased() {
+            Type::Bit | Type::Clock | Type::Reset | Type::AsyncReset => 1,
+            Type::Bits { width }
+            | Type::UInt { width }
+            | Type::SInt { width }
+            | Type::Enum { width, .. } => *width,
+            Type::Struct { fields, .. } => fields.iter().map(|f| f.ty.width()).sum(),
+            Type::Interface { fields, .. } => fields.iter().map(|f| f.ty.width()).sum(),
+            Type::Array { elem, len } => elem.width() * len,
+            Type::Generic { .. } | Type::GenericArray { .. } => {
+                panic!("generic types have no width before elaboration")
+            }
+            Type::Named { .. } | Type::Flipped { .. } => {
+                panic!("named types have no width before elaboration")
+            }
+            Type::Alias { .. } => unreachable!(),
+        }
+    }
+
+    /// Whether values of this type are interpreted as two's complement.
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self.unaliased(),
+            Type::SInt { .. }
+                | Type::Generic {
+                    kind: Vector::SInt,
+                    ..
+                }
+        )
+    }
+
+    /// Whether values of this type can be used in expressions. Clocks and
+    /// resets can only be forwarded or used to drive registers, interfaces
+    /// are only used through their fields, and arrays through indexing.
+    pub fn is_data(&self) -> bool {
+        !matches!(
+            self.unaliased(),
+            Type::Clock | Type::Reset | Type::AsyncReset | Type::Interface { .. }
+        ) && !self.is_array()
+    }
+
+    /// Whether this is an array type.
+    pub fn is_array(&self) -> bool {
+        matches!(
+            self.unaliased(),
+            Type::Array { .. } | Type::GenericArray { .. }
+        )
+    }
+
+    /// Whether this is an enum type, whose values only mix with values of
+    /// the same enum.
+    pub fn is_enum(&self) -> bool {
+        matches!(self.unaliased(), Type::Enum { .. })
+    }
+
+    /// Whether this is a struct type, whose values can only be moved around
+    /// whole or through their fields.
+    pub fn is_struct(&self) -> bool {
+        matches!(self.unaliased(), Type::Struct { .. })
+    }
+
+    /// Whether this is an interface type, which can only be connected to
+    /// instances whole or accessed through its fields.
+    pub fn is_interface(&self) -> bool {
+        matches!(self.unaliased(), Type::Interface { .. })
+    }
+
+    /// Looks up the type of a field of a struct or interface type.
+    pub fn field(&self, field: &str) -> Option<&Type> {
+        match self.unaliased() {
+            Type::Struct { fields, .. } => fields.iter().find(|f| f.name == field).map(|f| &f.ty),
+            Type::Interface { fields, .. } => {
+                fields.iter().find(|f| f.name == field).map(|f| &f.ty)
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Stmt {
-    Assign { id: String, ex: Expr },
-    Declare { id: String, ty: Type },
-    DeclareAssign { id: String, ty: Type, ex: Expr },
+    Const(Const),
+    /// Drives a signal, or one of its fields if it's a struct.
+    Assign {
+        id: String,
+        fields: Vec<String>,
+        ex: Expr,
+        pos: BytePos,
+    },
+    Declare {
+        id: String,
+        ty: Type,
+        attrs: Vec<Attr>,
+        pos: BytePos,
+    },
+    DeclareAssign {
+        id: String,
+        ty: Type,
+        ex: Expr,
+        attrs: Vec<Attr>,
+        pos: BytePos,
+    },
+    Reg {
+        id: String,
+        ty: Type,
+        clk: String,
+        rst: Option<String>,
+        init: Option<Expr>,
+        attrs: Vec<Attr>,
+        pos: BytePos,
+    },
+    /// An instance of `module`, which is brought in from `file` like a `use`
+    /// if the module is written as a path such as `lib::fifo::Fifo`. Plain
+    /// names leave `file` empty.
+    Inst {
+        id: String,
+        file: Vec<String>,
+        module: String,
+        params: Vec<Conn>,
+        conns: Vec<Conn>,
+        pos: BytePos,
+    },
+    /// A memory clocked by `clk`. Reads are synchronous, as in block RAM:
+    /// `id[addr]` gives the word at `addr` one cycle after the address is
+    /// presented, and a word written on the same edge reads as its old value.
+    Mem {
+        id: String,
+        ty: Type,
+        clk: String,
+        init: Option<MemInit>,
+        attrs: Vec<Attr>,
+        pos: BytePos,
+    },
+    /// Stores a word in a memory on every clock edge where `en` holds, or on
+    /// every edge if there is no enable.
+    MemWrite {
+        id: String,
+        addr: Expr,
+        ex: Expr,
+        en: Option<Expr>,
+        pos: BytePos,
+    },
+    /// Repeats its body for every value of `var` from `start` up to but
+    /// excluding `end`, unrolled during elaboration.
+    For {
+        var: String,
+        start: Expr,
+        end: Expr,
+        body: Vec<Stmt>,
+        pos: BytePos,
+    },
+    /// Keeps one of its branches depending on a constant condition, chosen
+    /// during elaboration.
+    If {
+        cond: Expr,
+        tstmts: Vec<Stmt>,
+        fstmts: Vec<Stmt>,
+        pos: BytePos,
+    },
+    /// A property for formal tools, which holds whenever its inputs change,
+    /// or on every edge of `clk` outside of reset if there is a clock.
+    Assert {
+        kind: Assertion,
+        ex: Expr,
+        clk: Option<String>,
+        rst: Option<String>,
+        pos: BytePos,
+    },
+}
+
+impl Stmt {
+    /// The position of the statement, at the name it declares or drives, or
+    /// at the expression it evaluates if it has no name.
+    pub fn pos(&self) -> BytePos {
+        match self {
+            Stmt::Const(c) => c.pos,
+            Stmt::Assign { pos, .. }
+            | Stmt::Declare { pos, .. }
+            | Stmt::DeclareAssign { pos, .. }
+            | Stmt::Reg { pos, .. }
+            | Stmt::Inst { pos, .. }
+            | Stmt::Mem { pos, .. }
+            | Stmt::MemWrite { pos, .. }
+            | Stmt::For { pos, .. }
+            | Stmt::If { pos, .. }
+            | Stmt::Assert { pos, .. } => *pos,
+        }
+    }
+}
+
+/// What formal tools do with a property.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Assertion {
+    /// Proves that it always holds.
+    Assert,
+    /// Takes for granted that it always holds.
+    Assume,
+    /// Finds a way for it to hold.
+    Cover,
+}
+
+/// The initial contents of a memory.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MemInit {
+    /// A constant array, or a constant holding one.
+    Const(Expr),
+    /// A file of hexadecimal words, loaded with `$readmemh`.
+    File(String),
+}
+
+/// A named connection to one of the ports or parameters of an instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conn {
+    pub port: String,
+    pub ex: Expr,
+    pub pos: BytePos,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
+    /// A binary operation, with `pos` locating the operator.
     Binary {
         lex: Box<Expr>,
         op: BinaryOp,
         rex: Box<Expr>,
+        pos: BytePos,
     },
     Unary {
         op: UnaryOp,
@@ -49,26 +731,120 @@ pub enum Expr {
     Paren {
         ex: Box<Expr>,
     },
+    If {
+        cond: Box<Expr>,
+        tex: Box<Expr>,
+        fex: Box<Expr>,
+    },
+    Match {
+        ex: Box<Expr>,
+        arms: Vec<Arm>,
+    },
+    Index {
+        ex: Box<Expr>,
+        idx: Box<Expr>,
+    },
+    Slice {
+        ex: Box<Expr>,
+        msb: Box<Expr>,
+        lsb: Box<Expr>,
+    },
+    Concat {
+        exs: Vec<Expr>,
+    },
+    Repeat {
+        count: Box<Expr>,
+        ex: Box<Expr>,
+    },
+    /// An array of values, element zero first.
+    Array {
+        exs: Vec<Expr>,
+    },
+    /// A call to a function, replaced by its body during elaboration.
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    /// Drives a value while `en` is set, and high impedance otherwise.
+    Tristate {
+        en: Box<Expr>,
+        ex: Box<Expr>,
+    },
+    /// Marks a value as deliberately crossing into another clock domain.
+    Cross {
+        ex: Box<Expr>,
+    },
+    /// A field of a struct or interface.
+    Field {
+        ex: Box<Expr>,
+        field: String,
+    },
+    /// A variant of an enum, written `Enum::Variant`.
+    Variant {
+        ty: String,
+        variant: String,
+    },
     Ident {
         id: String,
     },
     Litrl {
-        val: String,
+        val: BigUint,
+        width: Option<usize>,
     },
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arm {
+    pub pats: Vec<Pat>,
+    pub ex: Expr,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pat {
+    Litrl { val: BigUint, width: Option<usize> },
+    Variant { ty: String, variant: String },
+    Wildcard,
+}
+
+/// Binary operators.
+///
+/// Unless noted otherwise, the result is as wide as the wider operand.
+/// Results wider than the signal they are assigned to are truncated.
 #[derive(Clone, Debug, PartialEq)]
 pub enum BinaryOp {
+    /// Result is as wide as the left operand.
     ShiftLeft,
+    /// Result is as wide as the left operand; arithmetic for signed values.
     ShiftRight,
     BitAnd,
     BitXor,
     BitOr,
+    /// Result is one bit wider than the wider operand, to hold the carry.
+    Add,
+    /// Result is one bit wider than the wider operand, to hold the borrow.
+    Sub,
+    /// Result is as wide as both operands combined.
+    Mul,
+    /// Division and remainder are only available within constant declarations,
+    /// widths and parameter values.
+    Div,
+    Rem,
+    /// Comparisons result in a single bit.
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    /// Logical operators take and result in single bits.
+    LogicAnd,
+    LogicOr,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum UnaryOp {
     Negate,
+    LogicNot,
     ReductAnd,
     ReductXor,
     ReductOr,
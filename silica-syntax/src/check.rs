@@ -0,0 +1,1996 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, ToPrimitive};
+
+use crate::ast::*;
+use crate::elab::{inline_function, monomorphize, resolve_aliases, resolve_named};
+use crate::eval::{declare_const, eval, eval_array, file_env, resolve_type, Env, Value};
+use crate::visit::{walk_expr, Visitor};
+use crate::BytePos;
+
+/// Semantic error types.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    DuplicateDeclaration {
+        id: String,
+        pos: BytePos,
+    },
+    UndeclaredIdentifier {
+        id: String,
+        pos: BytePos,
+    },
+    MismatchedSignedness {
+        lty: Type,
+        rty: Type,
+        pos: BytePos,
+    },
+    /// An unsized value compared or combined with a signed one, which doesn't
+    /// fit beneath its sign bit.
+    LiteralOutOfRange {
+        val: BigInt,
+        ty: Type,
+        pos: BytePos,
+    },
+    NotIndexable {
+        ty: Type,
+        pos: BytePos,
+    },
+    NonConstantSlice {
+        pos: BytePos,
+    },
+    IndexOutOfBounds {
+        idx: usize,
+        width: usize,
+        pos: BytePos,
+    },
+    ReversedSlice {
+        msb: usize,
+        lsb: usize,
+        pos: BytePos,
+    },
+    UnsizedConcatOperand {
+        pos: BytePos,
+    },
+    NonConstantRepeat {
+        pos: BytePos,
+    },
+    ZeroRepeat {
+        pos: BytePos,
+    },
+    ExpectedBit {
+        ty: Type,
+        pos: BytePos,
+    },
+    MismatchedWidth {
+        lty: Type,
+        rty: Type,
+        pos: BytePos,
+    },
+    PatternOutOfRange {
+        val: BigUint,
+        width: usize,
+        pos: BytePos,
+    },
+    NonExhaustiveMatch {
+        pos: BytePos,
+    },
+    MissingResetValue {
+        id: String,
+        pos: BytePos,
+    },
+    NonConstantInit {
+        id: String,
+        pos: BytePos,
+    },
+    ExpectedData {
+        id: String,
+        ty: Type,
+        pos: BytePos,
+    },
+    ExpectedClock {
+        ty: Type,
+        pos: BytePos,
+    },
+    ExpectedReset {
+        ty: Type,
+        pos: BytePos,
+    },
+    MismatchedKind {
+        lty: Type,
+        rty: Type,
+        pos: BytePos,
+    },
+    ClockDomainCrossing {
+        id: String,
+        from: String,
+        to: String,
+        pos: BytePos,
+    },
+    DuplicateModule {
+        name: String,
+        pos: BytePos,
+        prev: BytePos,
+    },
+    MissingTop,
+    MultipleTops {
+        name: String,
+        pos: BytePos,
+        prev: BytePos,
+    },
+    UndeclaredModule {
+        name: String,
+        pos: BytePos,
+    },
+    RecursiveInstance {
+        name: String,
+        pos: BytePos,
+    },
+    UnknownPort {
+        id: String,
+        port: String,
+        pos: BytePos,
+    },
+    DuplicateConnection {
+        id: String,
+        port: String,
+        pos: BytePos,
+    },
+    MissingConnection {
+        id: String,
+        port: String,
+        pos: BytePos,
+    },
+    InvalidOutputConnection {
+        id: String,
+        port: String,
+        pos: BytePos,
+    },
+    UnknownParam {
+        id: String,
+        param: String,
+        pos: BytePos,
+    },
+    DuplicateParam {
+        id: String,
+        param: String,
+        pos: BytePos,
+    },
+    InvalidWidth {
+        pos: BytePos,
+        width: BigInt,
+    },
+    InvalidBound {
+        pos: BytePos,
+        bound: BigInt,
+    },
+    UnrollLimit {
+        pos: BytePos,
+        count: usize,
+    },
+    InvalidSuffix {
+        id: String,
+        pos: BytePos,
+    },
+    NonConstantExpression {
+        pos: BytePos,
+    },
+    Overflow {
+        pos: BytePos,
+        val: BigInt,
+    },
+    DivisionByZero {
+        pos: BytePos,
+    },
+    NonConstantDivision {
+        pos: BytePos,
+    },
+    AssignToConst {
+        id: String,
+        pos: BytePos,
+    },
+    DuplicateType {
+        name: String,
+    },
+    UndeclaredType {
+        name: String,
+    },
+    EmptyEnum {
+        name: String,
+    },
+    DuplicateVariant {
+        ty: String,
+        variant: String,
+    },
+    UnknownVariant {
+        ty: String,
+        variant: String,
+        pos: BytePos,
+    },
+    InvalidEncoding {
+        pos: BytePos,
+    },
+    MismatchedEnum {
+        lty: Type,
+        rty: Type,
+        pos: BytePos,
+    },
+    EnumOperation {
+        ty: Type,
+        pos: BytePos,
+    },
+    EmptyStruct {
+        name: String,
+    },
+    DuplicateField {
+        ty: String,
+        field: String,
+        pos: BytePos,
+    },
+    UnknownField {
+        ty: Type,
+        field: String,
+        pos: BytePos,
+    },
+    RecursiveType {
+        name: String,
+    },
+    MismatchedStruct {
+        lty: Type,
+        rty: Type,
+        pos: BytePos,
+    },
+    StructOperation {
+        ty: Type,
+        pos: BytePos,
+    },
+    EmptyInterface {
+        name: String,
+    },
+    ExpectedInterface {
+        ty: Type,
+    },
+    MismatchedInterface {
+        lty: Type,
+        rty: Type,
+        pos: BytePos,
+    },
+    InterfaceValue {
+        ty: Type,
+        pos: BytePos,
+    },
+    AssignToInput {
+        id: String,
+        pos: BytePos,
+    },
+    ExpectedTristate {
+        id: String,
+        pos: BytePos,
+    },
+    UndeclaredFunction {
+        name: String,
+        pos: BytePos,
+    },
+    DuplicateFunction {
+        name: String,
+        pos: BytePos,
+    },
+    MismatchedArguments {
+        name: String,
+        expected: usize,
+        found: usize,
+        pos: BytePos,
+    },
+    RecursiveFunction {
+        name: String,
+        pos: BytePos,
+    },
+    StatefulFunction {
+        name: String,
+        pos: BytePos,
+    },
+    UnexpectedTristate {
+        pos: BytePos,
+    },
+    InvalidInOutConnection {
+        id: String,
+        port: String,
+        pos: BytePos,
+    },
+    ExpectedArray {
+        ty: Type,
+        pos: BytePos,
+    },
+    UnexpectedArray {
+        pos: BytePos,
+    },
+    MismatchedLength {
+        expected: usize,
+        found: usize,
+        pos: BytePos,
+    },
+    InvalidElement {
+        ty: Type,
+        pos: BytePos,
+    },
+    InvalidAddress {
+        ty: Type,
+        pos: BytePos,
+    },
+    NotAMemory {
+        id: String,
+        pos: BytePos,
+    },
+}
+
+impl Error {
+    /// The position the error was found at, if it has one.
+    pub fn pos(&self) -> Option<BytePos> {
+        match self {
+            Error::DuplicateDeclaration { pos, .. }
+            | Error::UndeclaredIdentifier { pos, .. }
+            | Error::MismatchedSignedness { pos, .. }
+            | Error::LiteralOutOfRange { pos, .. }
+            | Error::NotIndexable { pos, .. }
+            | Error::NonConstantSlice { pos }
+            | Error::IndexOutOfBounds { pos, .. }
+            | Error::ReversedSlice { pos, .. }
+            | Error::UnsizedConcatOperand { pos }
+            | Error::NonConstantRepeat { pos }
+            | Error::ZeroRepeat { pos }
+            | Error::ExpectedBit { pos, .. }
+            | Error::MismatchedWidth { pos, .. }
+            | Error::PatternOutOfRange { pos, .. }
+            | Error::NonExhaustiveMatch { pos }
+            | Error::MissingResetValue { pos, .. }
+            | Error::NonConstantInit { pos, .. }
+            | Error::ExpectedData { pos, .. }
+            | Error::ExpectedClock { pos, .. }
+            | Error::ExpectedReset { pos, .. }
+            | Error::MismatchedKind { pos, .. }
+            | Error::ClockDomainCrossing { pos, .. }
+            | Error::DuplicateModule { pos, .. }
+            | Error::MultipleTops { pos, .. }
+            | Error::UndeclaredModule { pos, .. }
+            | Error::RecursiveInstance { pos, .. }
+            | Error::UnknownPort { pos, .. }
+            | Error::DuplicateConnection { pos, .. }
+            | Error::MissingConnection { pos, .. }
+            | Error::InvalidOutputConnection { pos, .. }
+            | Error::UnknownParam { pos, .. }
+            | Error::DuplicateParam { pos, .. }
+            | Error::InvalidWidth { pos, .. }
+            | Error::InvalidBound { pos, .. }
+            | Error::UnrollLimit { pos, .. }
+            | Error::InvalidSuffix { pos, .. }
+            | Error::NonConstantExpression { pos }
+            | Error::Overflow { pos, .. }
+            | Error::DivisionByZero { pos }
+            | Error::NonConstantDivision { pos }
+            | Error::AssignToConst { pos, .. }
+            | Error::UnknownVariant { pos, .. }
+            | Error::InvalidEncoding { pos }
+            | Error::MismatchedEnum { pos, .. }
+            | Error::EnumOperation { pos, .. }
+            | Error::DuplicateField { pos, .. }
+            | Error::UnknownField { pos, .. }
+            | Error::MismatchedStruct { pos, .. }
+            | Error::StructOperation { pos, .. }
+            | Error::MismatchedInterface { pos, .. }
+            | Error::InterfaceValue { pos, .. }
+            | Error::AssignToInput { pos, .. }
+            | Error::ExpectedTristate { pos, .. }
+            | Error::UndeclaredFunction { pos, .. }
+            | Error::DuplicateFunction { pos, .. }
+            | Error::MismatchedArguments { pos, .. }
+            | Error::RecursiveFunction { pos, .. }
+            | Error::StatefulFunction { pos, .. }
+            | Error::UnexpectedTristate { pos }
+            | Error::InvalidInOutConnection { pos, .. }
+            | Error::ExpectedArray { pos, .. }
+            | Error::UnexpectedArray { pos }
+            | Error::MismatchedLength { pos, .. }
+            | Error::InvalidElement { pos, .. }
+            | Error::InvalidAddress { pos, .. }
+            | Error::NotAMemory { pos, .. } => Some(*pos),
+            _ => None,
+        }
+    }
+
+    /// The position of the earlier definition the error clashes with, if it
+    /// names one.
+    pub fn prev(&self) -> Option<BytePos> {
+        match self {
+            Error::DuplicateModule { prev, .. } | Error::MultipleTops { prev, .. } => Some(*prev),
+            _ => None,
+        }
+    }
+}
+
+/// Problems that don't stop a design from compiling.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    UnknownAttribute { name: String, pos: BytePos },
+}
+
+impl Warning {
+    /// The position the warning was found at.
+    pub fn pos(&self) -> BytePos {
+        match self {
+            Warning::UnknownAttribute { pos, .. } => *pos,
+        }
+    }
+}
+
+/// Finds the attributes of modules, ports and declarations that aren't
+/// passed on to synthesis, which are otherwise ignored.
+pub fn warnings(ast: &Ast) -> Vec<Warning> {
+    struct Attrs {
+        warnings: Vec<Warning>,
+    }
+
+    impl Visitor for Attrs {
+        fn visit_attr(&mut self, a: &Attr) {
+            if !a.is_synthesis() {
+                self.warnings.push(Warning::UnknownAttribute {
+                    name: a.name.clone(),
+                    pos: a.pos,
+                });
+            }
+        }
+    }
+
+    let mut attrs = Attrs {
+        warnings: Vec::new(),
+    };
+    // Enums only take an encoding, and nothing of theirs reaches synthesis.
+    for e in &ast.enums {
+        for a in e.attrs.iter().filter(|a| a.name != "encoding") {
+            attrs.warnings.push(Warning::UnknownAttribute {
+                name: a.name.clone(),
+                pos: a.pos,
+            });
+        }
+    }
+    for f in &ast.fns {
+        for stmt in &f.body {
+            attrs.visit_stmt(stmt);
+        }
+    }
+    for m in &ast.mods {
+        attrs.visit_mod(m);
+    }
+    attrs.warnings
+}
+
+/// Checks a parsed AST for semantic errors.
+pub fn check_ast(ast: &Ast) -> Result<(), Error> {
+    let mut names: HashMap<&String, BytePos> = HashMap::new();
+    for m in &ast.mods {
+        if let Some(prev) = names.insert(&m.name, m.pos) {
+            return Err(Error::DuplicateModule {
+                name: m.name.clone(),
+                pos: m.pos,
+                prev,
+            });
+        }
+    }
+
+    // Aliases are resolved up front, since any other type may refer to them.
+    let ast = &resolve_aliases(ast);
+    check_enums(ast)?;
+    let env = file_env(ast)?;
+    check_structs(ast, &env)?;
+    check_interfaces(ast, &env)?;
+    check_aliases(ast, &env)?;
+    check_functions(ast)?;
+
+    let top = ast.top().ok_or(Error::MissingTop)?;
+    if let Some(m) = ast.mods.iter().find(|m| m.top && m.name != top.name) {
+        return Err(Error::MultipleTops {
+            name: m.name.clone(),
+            pos: m.pos,
+            prev: top.pos,
+        });
+    }
+
+    for m in &ast.mods {
+        check_recursion(ast, m, &mut vec![&m.name])?;
+    }
+
+    // Modules are checked once for every set of parameter values in use.
+    let ast = monomorphize(ast)?;
+    for m in &ast.mods {
+        check_mod(&ast, m)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that enums are uniquely named, with distinct variants and a known
+/// encoding.
+fn check_enums(ast: &Ast) -> Result<(), Error> {
+    let mut names = HashSet::new();
+    for e in &ast.enums {
+        if !names.insert(&e.name) {
+            return Err(Error::DuplicateType {
+                name: e.name.clone(),
+            });
+        }
+        if e.variants.is_empty() {
+            return Err(Error::EmptyEnum {
+                name: e.name.clone(),
+            });
+        }
+        for (idx, variant) in e.variants.iter().enumerate() {
+            if e.variants[..idx].contains(variant) {
+                return Err(Error::DuplicateVariant {
+                    ty: e.name.clone(),
+                    variant: variant.clone(),
+                });
+            }
+        }
+        if let Some(attr) = e.attrs.iter().find(|a| a.name == "encoding") {
+            if e.encoding().is_none() {
+                return Err(Error::InvalidEncoding { pos: attr.pos });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that structs are uniquely named, with distinct fields holding data,
+/// and don't contain themselves.
+fn check_structs(ast: &Ast, env: &Env) -> Result<(), Error> {
+    let mut names: HashSet<&String> = ast.enums.iter().map(|e| &e.name).collect();
+    for s in &ast.structs {
+        if !names.insert(&s.name) {
+            return Err(Error::DuplicateType {
+                name: s.name.clone(),
+            });
+        }
+        if s.fields.is_empty() {
+            return Err(Error::EmptyStruct {
+                name: s.name.clone(),
+            });
+        }
+        for (idx, field) in s.fields.iter().enumerate() {
+            if s.fields[..idx].iter().any(|f| f.name == field.name) {
+                return Err(Error::DuplicateField {
+                    ty: s.name.clone(),
+                    field: field.name.clone(),
+                    pos: field.pos,
+                });
+            }
+        }
+        check_type_recursion(ast, &s.name, &mut vec![&s.name])?;
+
+        let named = Type::Named {
+            name: s.name.clone(),
+        };
+        if let Type::Struct { fields, .. } = resolve_named(ast, &named, env)? {
+            if let Some(field) = fields.iter().find(|f| !f.ty.is_data()) {
+                return Err(Error::ExpectedData {
+                    id: field.name.clone(),
+                    ty: field.ty.clone(),
+                    pos: field.pos,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that interfaces are uniquely named, with distinct fields, and
+/// don't contain themselves.
+fn check_interfaces(ast: &Ast, env: &Env) -> Result<(), Error> {
+    let mut names: HashSet<&String> = ast
+        .enums
+        .iter()
+        .map(|e| &e.name)
+        .chain(ast.structs.iter().map(|s| &s.name))
+        .collect();
+    for i in &ast.interfaces {
+        if !names.insert(&i.name) {
+            return Err(Error::DuplicateType {
+                name: i.name.clone(),
+            });
+        }
+        if i.fields.is_empty() {
+            return Err(Error::EmptyInterface {
+                name: i.name.clone(),
+            });
+        }
+        for (idx, field) in i.fields.iter().enumerate() {
+            if i.fields[..idx].iter().any(|f| f.name == field.name) {
+                return Err(Error::DuplicateField {
+                    ty: i.name.clone(),
+                    field: field.name.clone(),
+                    pos: field.pos,
+                });
+            }
+        }
+        check_type_recursion(ast, &i.name, &mut vec![&i.name])?;
+
+        let named = Type::Named {
+            name: i.name.clone(),
+        };
+        resolve_named(ast, &named, env)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that type aliases are uniquely named and stand for a declared type.
+fn check_aliases(ast: &Ast, env: &Env) -> Result<(), Error> {
+    let mut names: HashSet<&String> = ast
+        .enums
+        .iter()
+        .map(|e| &e.name)
+        .chain(ast.structs.iter().map(|s| &s.name))
+        .chain(ast.interfaces.iter().map(|i| &i.name))
+        .collect();
+    for a in &ast.aliases {
+        if !names.insert(&a.name) {
+            return Err(Error::DuplicateType {
+                name: a.name.clone(),
+            });
+        }
+        resolve_named(ast, &a.ty, env)?;
+    }
+
+    Ok(())
+}
+
+/// Rejects structs and interfaces that end up containing themselves.
+fn check_type_recursion<'a>(
+    ast: &'a Ast,
+    name: &str,
+    stack: &mut Vec<&'a String>,
+) -> Result<(), Error> {
+    let fields: Vec<&Type> = match (ast.structure(name), ast.interface(name)) {
+        (Some(s), _) => s.fields.iter().map(|f| &f.ty).collect(),
+        (None, Some(i)) => i.fields.iter().map(|f| &f.ty).collect(),
+        (None, None) => return Ok(()),
+    };
+    for mut ty in fields {
+        while let Type::Flipped { ty: inner } | Type::Alias { ty: inner, .. } = ty {
+            ty = inner;
+        }
+        if let Type::Named { name } = ty {
+            if stack.contains(&name) {
+                return Err(Error::RecursiveType { name: name.clone() });
+            }
+            stack.push(name);
+            check_type_recursion(ast, name, stack)?;
+            stack.pop();
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that functions are uniquely named, and that their bodies only
+/// compute values from their parameters and constants.
+fn check_functions(ast: &Ast) -> Result<(), Error> {
+    let mut names = HashSet::new();
+    for f in &ast.fns {
+        if !names.insert(&f.name) {
+            return Err(Error::DuplicateFunction {
+                name: f.name.clone(),
+                pos: f.pos,
+            });
+        }
+        inline_function(ast, f)?;
+    }
+
+    Ok(())
+}
+
+/// Rejects modules that end up instantiating themselves, whether or not the
+/// instances survive elaboration.
+fn check_recursion<'a>(ast: &'a Ast, m: &'a Mod, stack: &mut Vec<&'a String>) -> Result<(), Error> {
+    for (module, pos) in instantiated(&m.stmts) {
+        if stack.contains(&module) {
+            return Err(Error::RecursiveInstance {
+                name: module.clone(),
+                pos,
+            });
+        }
+        if let Some(m) = ast.module(module) {
+            stack.push(module);
+            check_recursion(ast, m, stack)?;
+            stack.pop();
+        }
+    }
+
+    Ok(())
+}
+
+/// The modules instantiated by some statements, including within generation
+/// blocks, along with the positions of the instances.
+fn instantiated(stmts: &[Stmt]) -> Vec<(&String, BytePos)> {
+    stmts
+        .iter()
+        .flat_map(|stmt| match stmt {
+            Stmt::Inst { module, pos, .. } => vec![(module, *pos)],
+            Stmt::For { body, .. } => instantiated(body),
+            Stmt::If { tstmts, fstmts, .. } => {
+                let mut modules = instantiated(tstmts);
+                modules.extend(instantiated(fstmts));
+                modules
+            }
+            _ => vec![],
+        })
+        .collect()
+}
+
+fn check_mod(ast: &Ast, m: &Mod) -> Result<(), Error> {
+    let mut scope = Scope::new(ast, m)?;
+
+    // Arrays are only found in memories and constants.
+    let declared = m.ports.iter().map(|p| (&p.name, &p.ty, p.pos));
+    let declared = declared.chain(m.stmts.iter().filter_map(|stmt| match stmt {
+        Stmt::Declare { id, ty, pos, .. } | Stmt::DeclareAssign { id, ty, pos, .. } => {
+            Some((id, ty, *pos))
+        }
+        _ => None,
+    }));
+    for (id, ty, pos) in declared {
+        if ty.is_array() {
+            return Err(Error::ExpectedData {
+                id: id.clone(),
+                ty: ty.clone(),
+                pos,
+            });
+        }
+    }
+
+    for stmt in &m.stmts {
+        let pos = stmt.pos();
+        scope.pos = pos;
+        match stmt {
+            Stmt::Const(_) => {}
+            Stmt::Assign { id, fields, ex, .. } => {
+                if scope.constant(id).is_some() {
+                    return Err(Error::AssignToConst {
+                        id: id.clone(),
+                        pos,
+                    });
+                }
+                if scope.memory(id).is_some() {
+                    return Err(Error::ExpectedData {
+                        id: id.clone(),
+                        ty: scope.lookup(id)?.clone(),
+                        pos,
+                    });
+                }
+                let ty = scope.lookup_path(id, fields)?;
+                let dir = scope.direction(id, fields);
+                if dir == Some(Dir::Input) {
+                    return Err(Error::AssignToInput {
+                        id: id.clone(),
+                        pos,
+                    });
+                }
+                match ex {
+                    // tri-state buffers drive wires, never registers
+                    Expr::Tristate { en, ex } => {
+                        if scope.reg(id).is_some() || !ty.is_data() || ty.is_struct() {
+                            return Err(Error::UnexpectedTristate { pos });
+                        }
+                        scope.expect_bit(en)?;
+                        check_assign(&scope, &ty, ex)?;
+                    }
+                    _ if dir == Some(Dir::InOut) => {
+                        return Err(Error::ExpectedTristate {
+                            id: id.clone(),
+                            pos,
+                        });
+                    }
+                    _ => check_assign(&scope, &ty, ex)?,
+                }
+            }
+            Stmt::Declare { .. } => {}
+            Stmt::DeclareAssign { ty, ex, .. } => {
+                check_assign(&scope, ty, ex)?;
+            }
+            Stmt::Reg {
+                id,
+                ty,
+                clk,
+                rst,
+                init,
+                ..
+            } => {
+                if !ty.is_data() {
+                    return Err(Error::ExpectedData {
+                        id: id.clone(),
+                        ty: ty.clone(),
+                        pos,
+                    });
+                }
+                check_clocking(&scope, clk, rst.as_ref())?;
+                match init {
+                    Some(init) if !scope.is_constant(init) => {
+                        return Err(Error::NonConstantInit {
+                            id: id.clone(),
+                            pos,
+                        });
+                    }
+                    Some(init) => check_assign(&scope, ty, init)?,
+                    None if rst.is_some() => {
+                        return Err(Error::MissingResetValue {
+                            id: id.clone(),
+                            pos,
+                        });
+                    }
+                    None => {}
+                }
+            }
+            Stmt::Inst {
+                id, module, conns, ..
+            } => {
+                check_inst(ast, &scope, id, module, conns)?;
+            }
+            Stmt::Mem {
+                id, ty, clk, init, ..
+            } => {
+                let (elem, len) = match ty.unaliased() {
+                    Type::Array { elem, len } => (elem, *len),
+                    _ => {
+                        return Err(Error::ExpectedArray {
+                            ty: ty.clone(),
+                            pos,
+                        })
+                    }
+                };
+                if !elem.is_data() || elem.is_struct() {
+                    return Err(Error::InvalidElement {
+                        ty: (**elem).clone(),
+                        pos,
+                    });
+                }
+                let clk_ty = scope.lookup(clk)?;
+                if *clk_ty != Type::Clock {
+                    return Err(Error::ExpectedClock {
+                        ty: clk_ty.clone(),
+                        pos,
+                    });
+                }
+                if let Some(MemInit::Const(ex)) = init {
+                    let exs: Vec<&Expr> = match ex {
+                        Expr::Array { exs } => exs.iter().collect(),
+                        ex => vec![ex],
+                    };
+                    if !exs.iter().all(|ex| scope.is_constant(ex)) {
+                        return Err(Error::NonConstantInit {
+                            id: id.clone(),
+                            pos,
+                        });
+                    }
+                    eval_array(ex, &scope.consts, elem, len, pos)?;
+                }
+            }
+            Stmt::MemWrite {
+                id, addr, ex, en, ..
+            } => {
+                let elem = match scope.memory_of(&Expr::Ident { id: id.clone() }) {
+                    Some((elem, len)) => {
+                        scope.check_address(addr, len)?;
+                        elem.clone()
+                    }
+                    None => {
+                        return Err(Error::NotAMemory {
+                            id: id.clone(),
+                            pos,
+                        })
+                    }
+                };
+                check_assign(&scope, &elem, ex)?;
+                if let Some(en) = en {
+                    scope.expect_bit(en)?;
+                }
+            }
+            Stmt::Assert { ex, clk, rst, .. } => {
+                check_struct_operands(&scope, ex)?;
+                scope.expect_bit(ex)?;
+                if let Some(clk) = clk {
+                    check_clocking(&scope, clk, rst.as_ref())?;
+                }
+            }
+            Stmt::For { .. } | Stmt::If { .. } => {
+                unreachable!("generation blocks should be unrolled before checking")
+            }
+        }
+    }
+
+    check_domains(m, &scope)
+}
+
+/// Checks that a clock, and a reset if there is one, are of the right kind.
+fn check_clocking(scope: &Scope, clk: &str, rst: Option<&String>) -> Result<(), Error> {
+    let ty = scope.lookup(clk)?;
+    if *ty != Type::Clock {
+        return Err(Error::ExpectedClock {
+            ty: ty.clone(),
+            pos: scope.pos,
+        });
+    }
+    if let Some(rst) = rst {
+        let ty = scope.lookup(rst)?;
+        if !matches!(ty.unaliased(), Type::Reset | Type::AsyncReset) {
+            return Err(Error::ExpectedReset {
+                ty: ty.clone(),
+                pos: scope.pos,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that every port of an instance is connected exactly once. Inputs
+/// are connected like assignments, while outputs must drive a signal of the
+/// same type that isn't a register.
+fn check_inst(
+    ast: &Ast,
+    scope: &Scope,
+    id: &str,
+    module: &str,
+    conns: &[Conn],
+) -> Result<(), Error> {
+    let m = ast.module(module).ok_or_else(|| Error::UndeclaredModule {
+        name: module.to_string(),
+        pos: scope.pos,
+    })?;
+
+    let mut connected = HashSet::new();
+    for conn in conns {
+        let port = m
+            .ports
+            .iter()
+            .find(|p| p.name == conn.port)
+            .ok_or_else(|| Error::UnknownPort {
+                id: id.to_string(),
+                port: conn.port.clone(),
+                pos: conn.pos,
+            })?;
+        if !connected.insert(&conn.port) {
+            return Err(Error::DuplicateConnection {
+                id: id.to_string(),
+                port: conn.port.clone(),
+                pos: conn.pos,
+            });
+        }
+
+        if port.ty.is_interface() {
+            check_interface_connection(scope, id, port, conn)?;
+            continue;
+        }
+        match port.dir {
+            Dir::Input => check_assign(scope, &port.ty, &conn.ex)?,
+            Dir::Output => {
+                let ty = match &conn.ex {
+                    Expr::Ident { id }
+                        if scope.reg(id).is_none() && scope.constant(id).is_none() =>
+                    {
+                        scope.lookup(id)?
+                    }
+                    _ => {
+                        return Err(Error::InvalidOutputConnection {
+                            id: id.to_string(),
+                            port: conn.port.clone(),
+                            pos: conn.pos,
+                        });
+                    }
+                };
+                check_connection(ty, &port.ty, conn.pos)?;
+            }
+            Dir::InOut => {
+                let invalid = || Error::InvalidInOutConnection {
+                    id: id.to_string(),
+                    port: conn.port.clone(),
+                    pos: conn.pos,
+                };
+                let (root, path) = place_path(&conn.ex).ok_or_else(invalid)?;
+                let ty = scope.lookup_path(root, &path)?;
+                if !scope.is_bidirectional(root, &path) {
+                    return Err(invalid());
+                }
+                check_connection(&ty, &port.ty, conn.pos)?;
+            }
+        }
+    }
+
+    match m.ports.iter().find(|p| !connected.contains(&p.name)) {
+        Some(port) => Err(Error::MissingConnection {
+            id: id.to_string(),
+            port: port.name.clone(),
+            pos: scope.pos,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that an interface port is connected to a signal or port of the same
+/// interface, and that any fields driven by the instance aren't inputs of the
+/// enclosing module.
+fn check_interface_connection(
+    scope: &Scope,
+    id: &str,
+    port: &Port,
+    conn: &Conn,
+) -> Result<(), Error> {
+    let invalid = || Error::InvalidOutputConnection {
+        id: id.to_string(),
+        port: port.name.clone(),
+        pos: conn.pos,
+    };
+    let (root, mut path) = place_path(&conn.ex).ok_or_else(invalid)?;
+    let ty = scope.lookup_path(root, &path)?;
+    match (ty.unaliased(), port.ty.unaliased()) {
+        (
+            Type::Interface { name: lname, .. },
+            Type::Interface {
+                name: rname,
+                fields,
+            },
+        ) if lname == rname => {
+            for field in fields {
+                path.push(field.name.clone());
+                let invalid_field = match port.dir.compose(&field.dir) {
+                    Dir::Output => scope.direction(root, &path) == Some(Dir::Input),
+                    Dir::InOut => !scope.is_bidirectional(root, &path),
+                    Dir::Input => false,
+                };
+                if invalid_field {
+                    return Err(invalid());
+                }
+                path.pop();
+            }
+            Ok(())
+        }
+        _ => Err(Error::MismatchedInterface {
+            lty: ty.clone(),
+            rty: port.ty.clone(),
+            pos: conn.pos,
+        }),
+    }
+}
+
+/// Splits a place such as `a.b.c` into the signal it starts from and the
+/// fields followed from there.
+fn place_path(e: &Expr) -> Option<(&String, Vec<String>)> {
+    match e {
+        Expr::Ident { id } => Some((id, vec![])),
+        Expr::Field { ex, field } => {
+            let (id, mut fields) = place_path(ex)?;
+            fields.push(field.clone());
+            Some((id, fields))
+        }
+        Expr::Paren { ex } => place_path(ex),
+        _ => None,
+    }
+}
+
+/// Checks that a signal can be driven directly by an output port.
+fn check_connection(ty: &Type, port: &Type, pos: BytePos) -> Result<(), Error> {
+    let (lty, rty) = (ty.clone(), port.clone());
+    if !ty.is_data() || !port.is_data() {
+        if ty != port {
+            return Err(Error::MismatchedKind { lty, rty, pos });
+        }
+    } else if (ty.is_enum() || port.is_enum()) && ty != port {
+        return Err(Error::MismatchedEnum { lty, rty, pos });
+    } else if (ty.is_struct() || port.is_struct()) && ty != port {
+        return Err(Error::MismatchedStruct { lty, rty, pos });
+    } else if ty.width() != port.width() {
+        return Err(Error::MismatchedWidth { lty, rty, pos });
+    } else if ty.is_signed() != port.is_signed() {
+        return Err(Error::MismatchedSignedness { lty, rty, pos });
+    }
+
+    Ok(())
+}
+
+fn check_assign(scope: &Scope, ty: &Type, ex: &Expr) -> Result<(), Error> {
+    check_struct_operands(scope, ex)?;
+
+    // Interfaces are only connected whole to instances.
+    if ty.is_interface() {
+        return Err(Error::InterfaceValue {
+            ty: ty.clone(),
+            pos: scope.pos,
+        });
+    }
+
+    // Clocks and resets can only be driven by another signal of the same kind.
+    if !ty.is_data() {
+        let ex_ty = match ex {
+            Expr::Ident { id } => scope.lookup(id)?.clone(),
+            _ => scope.type_of(ex)?,
+        };
+        if ex_ty.is_interface() {
+            return Err(Error::InterfaceValue {
+                ty: ex_ty,
+                pos: scope.pos,
+            });
+        }
+        if *ty != ex_ty {
+            return Err(Error::MismatchedKind {
+                lty: ty.clone(),
+                rty: ex_ty,
+                pos: scope.pos,
+            });
+        }
+        return Ok(());
+    }
+
+    // Enums only mix with values of the same enum.
+    let ex_ty = scope.type_of(ex)?;
+    if ex_ty.is_interface() {
+        return Err(Error::InterfaceValue {
+            ty: ex_ty,
+            pos: scope.pos,
+        });
+    }
+    if (ty.is_enum() || ex_ty.is_enum()) && *ty != ex_ty {
+        return Err(Error::MismatchedEnum {
+            lty: ty.clone(),
+            rty: ex_ty,
+            pos: scope.pos,
+        });
+    }
+    if (ty.is_struct() || ex_ty.is_struct()) && *ty != ex_ty {
+        return Err(Error::MismatchedStruct {
+            lty: ty.clone(),
+            rty: ex_ty,
+            pos: scope.pos,
+        });
+    }
+    if !scope.is_unsized(ex) && ty.is_signed() != ex_ty.is_signed() {
+        return Err(Error::MismatchedSignedness {
+            lty: ty.clone(),
+            rty: ex_ty,
+            pos: scope.pos,
+        });
+    }
+
+    Ok(())
+}
+
+/// Rejects structs and interfaces used as operands, since they can only be
+/// moved around whole or through their fields.
+fn check_struct_operands(scope: &Scope, e: &Expr) -> Result<(), Error> {
+    let operands: Vec<&Expr> = match e {
+        Expr::Paren { ex } | Expr::Cross { ex } | Expr::Field { ex, .. } => {
+            return check_struct_operands(scope, ex);
+        }
+        Expr::Binary { lex, rex, .. } => vec![lex, rex],
+        Expr::Unary { ex, .. } => vec![ex],
+        Expr::Tristate { en, ex } => vec![en, ex],
+        Expr::Call { args, .. } => args.iter().collect(),
+        Expr::If { cond, tex, fex } => vec![cond, tex, fex],
+        Expr::Match { ex, arms } => std::iter::once(&**ex)
+            .chain(arms.iter().map(|arm| &arm.ex))
+            .collect(),
+        Expr::Index { idx, .. } if scope.memory_of(e).is_some() => vec![idx],
+        Expr::Index { ex, idx } => vec![ex, idx],
+        Expr::Slice { ex, msb, lsb } => vec![ex, msb, lsb],
+        Expr::Concat { exs } | Expr::Array { exs } => exs.iter().collect(),
+        Expr::Repeat { count, ex } => vec![count, ex],
+        Expr::Variant { .. } | Expr::Ident { .. } | Expr::Litrl { .. } => vec![],
+    };
+    for ex in operands {
+        let ty = scope.type_of(ex)?;
+        if ty.is_struct() {
+            return Err(Error::StructOperation { ty, pos: scope.pos });
+        }
+        if ty.is_interface() {
+            return Err(Error::InterfaceValue { ty, pos: scope.pos });
+        }
+        check_struct_operands(scope, ex)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that registers are only fed from their own clock domain, unless the
+/// crossing is marked with `cross`. Combinational signals belong to every
+/// domain they read from, while inputs belong to none.
+fn check_domains(m: &Mod, scope: &Scope) -> Result<(), Error> {
+    let drivers: Vec<(&String, &Expr, BytePos)> = m
+        .stmts
+        .iter()
+        .flat_map(|stmt| match stmt {
+            Stmt::Assign { id, ex, pos, .. } | Stmt::DeclareAssign { id, ex, pos, .. } => {
+                vec![(id, ex, *pos)]
+            }
+            // everything a memory write samples is clocked in
+            Stmt::MemWrite {
+                id,
+                addr,
+                ex,
+                en,
+                pos,
+            } => std::iter::once(addr)
+                .chain(Some(ex))
+                .chain(en)
+                .map(|ex| (id, ex, *pos))
+                .collect(),
+            _ => vec![],
+        })
+        .collect();
+    let is_clocked = |id: &str| scope.reg(id).is_some() || scope.memory(id).is_some();
+
+    // Clocks forwarded from another clock share its domain.
+    let clock_root = |clk: &String| {
+        let mut root = clk;
+        for _ in 0..drivers.len() {
+            match drivers.iter().find(|(id, ..)| *id == root) {
+                Some((_, Expr::Ident { id }, _)) => root = id,
+                _ => break,
+            }
+        }
+        root.clone()
+    };
+
+    let mut domains: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for (id, reg) in &scope.regs {
+        let domain = std::iter::once(clock_root(&reg.clk)).collect();
+        domains.insert(id.clone(), domain);
+    }
+    for (id, mem) in &scope.mems {
+        let domain = std::iter::once(clock_root(&mem.clk)).collect();
+        domains.insert(id.clone(), domain);
+    }
+
+    loop {
+        let mut changed = false;
+        for (id, ex, _) in &drivers {
+            if is_clocked(id) {
+                continue;
+            }
+            let reads = read_domains(ex, &domains);
+            let domain = domains.entry(id.to_string()).or_default();
+            let len = domain.len();
+            domain.extend(reads);
+            changed |= domain.len() != len;
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    for (id, ex, pos) in &drivers {
+        if let Some(to) = domains.get(*id).filter(|_| is_clocked(id)) {
+            let to = to.iter().next().unwrap();
+            if let Some(from) = read_domains(ex, &domains).into_iter().find(|d| d != to) {
+                return Err(Error::ClockDomainCrossing {
+                    id: id.to_string(),
+                    from,
+                    to: to.clone(),
+                    pos: *pos,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the clock domains an expression reads from, skipping over any
+/// marked crossings.
+fn read_domains(e: &Expr, domains: &HashMap<String, BTreeSet<String>>) -> BTreeSet<String> {
+    struct Reads<'a> {
+        domains: &'a HashMap<String, BTreeSet<String>>,
+        found: BTreeSet<String>,
+    }
+
+    impl<'a> Visitor for Reads<'a> {
+        fn visit_expr(&mut self, e: &Expr) {
+            match e {
+                Expr::Cross { .. } => {}
+                Expr::Ident { id } => {
+                    if let Some(domain) = self.domains.get(id) {
+                        self.found.extend(domain.iter().cloned());
+                    }
+                }
+                _ => walk_expr(self, e),
+            }
+        }
+    }
+
+    let mut reads = Reads {
+        domains,
+        found: BTreeSet::new(),
+    };
+    reads.visit_expr(e);
+    reads.found
+}
+
+/// The signals visible within a module, along with their types.
+pub struct Scope {
+    signals: HashMap<String, Type>,
+    ports: HashMap<String, Dir>,
+    regs: HashMap<String, Reg>,
+    mems: HashMap<String, Memory>,
+    insts: HashMap<String, String>,
+    consts: Env,
+    enums: HashMap<String, Enum>,
+    /// The position of the port or statement being checked, which errors
+    /// found within it point to.
+    pos: BytePos,
+}
+
+/// The clock a memory is read and written on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Memory {
+    pub clk: String,
+}
+
+/// The clock, reset and reset value of a register.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reg {
+    pub clk: String,
+    pub rst: Option<String>,
+    pub init: Option<Expr>,
+}
+
+impl Scope {
+    /// Collects the constants of a file along with the ports and declarations
+    /// of one of its modules, which must have been elaborated.
+    pub fn new(ast: &Ast, m: &Mod) -> Result<Scope, Error> {
+        let mut scope = Scope {
+            signals: HashMap::new(),
+            ports: HashMap::new(),
+            regs: HashMap::new(),
+            mems: HashMap::new(),
+            insts: HashMap::new(),
+            consts: Env::new(),
+            enums: ast
+                .enums
+                .iter()
+                .map(|e| (e.name.clone(), e.clone()))
+                .collect(),
+            pos: 0,
+        };
+
+        for c in &ast.consts {
+            scope.declare_const(c)?;
+        }
+        // Parameters take their default values, like constants.
+        for param in &m.params {
+            scope.declare_const(&Const {
+                name: param.name.clone(),
+                ty: None,
+                ex: param.default.clone(),
+                pos: param.pos,
+            })?;
+        }
+        for port in &m.ports {
+            scope.pos = port.pos;
+            scope.declare(&port.name, &port.ty)?;
+            scope.ports.insert(port.name.clone(), port.dir.clone());
+        }
+        for stmt in &m.stmts {
+            scope.pos = stmt.pos();
+            match stmt {
+                Stmt::Declare { id, ty, .. } | Stmt::DeclareAssign { id, ty, .. } => {
+                    scope.declare(id, ty)?;
+                }
+                Stmt::Reg {
+                    id,
+                    ty,
+                    clk,
+                    rst,
+                    init,
+                    ..
+                } => {
+                    scope.declare(id, ty)?;
+                    let reg = Reg {
+                        clk: clk.clone(),
+                        rst: rst.clone(),
+                        init: init.clone(),
+                    };
+                    scope.regs.insert(id.clone(), reg);
+                }
+                Stmt::Inst { id, module, .. } => {
+                    if scope.is_declared(id) {
+                        return Err(Error::DuplicateDeclaration {
+                            id: id.clone(),
+                            pos: scope.pos,
+                        });
+                    }
+                    scope.insts.insert(id.clone(), module.clone());
+                }
+                Stmt::Mem { id, ty, clk, .. } => {
+                    scope.declare(id, ty)?;
+                    let mem = Memory { clk: clk.clone() };
+                    scope.mems.insert(id.clone(), mem);
+                }
+                Stmt::Const(c) => scope.declare_const(c)?,
+                Stmt::Assign { .. } | Stmt::MemWrite { .. } | Stmt::Assert { .. } => {}
+                Stmt::For { .. } | Stmt::If { .. } => {
+                    unreachable!("generation blocks should be unrolled before scoping")
+                }
+            }
+        }
+
+        Ok(scope)
+    }
+
+    fn declare_const(&mut self, c: &Const) -> Result<(), Error> {
+        self.pos = c.pos;
+        let ty = match &c.ty {
+            Some(ty) => Some(resolve_type(ty, &self.consts)?),
+            None => None,
+        };
+        let v = declare_const(&mut self.consts, c)?;
+        let ty = ty.unwrap_or(Type::UInt { width: v.width() });
+        self.declare(&c.name, &ty)
+    }
+
+    fn declare(&mut self, id: &str, ty: &Type) -> Result<(), Error> {
+        if self.is_declared(id) {
+            return Err(Error::DuplicateDeclaration {
+                id: id.to_string(),
+                pos: self.pos,
+            });
+        }
+        self.signals.insert(id.to_string(), ty.clone());
+
+        Ok(())
+    }
+
+    /// Whether a signal or instance of this name exists.
+    pub fn is_declared(&self, id: &str) -> bool {
+        self.signals.contains_key(id) || self.insts.contains_key(id)
+    }
+
+    /// Looks up the declared type of a signal.
+    pub fn lookup(&self, id: &str) -> Result<&Type, Error> {
+        self.signals
+            .get(id)
+            .ok_or_else(|| Error::UndeclaredIdentifier {
+                id: id.to_string(),
+                pos: self.pos,
+            })
+    }
+
+    /// Looks up the type of a signal, or of a field within it.
+    pub fn lookup_path(&self, id: &str, fields: &[String]) -> Result<Type, Error> {
+        let mut ty = self.lookup(id)?.clone();
+        for field in fields {
+            ty = match ty.field(field) {
+                Some(fty) => fty.clone(),
+                None => {
+                    return Err(Error::UnknownField {
+                        ty,
+                        field: field.clone(),
+                        pos: self.pos,
+                    });
+                }
+            };
+        }
+
+        Ok(ty)
+    }
+
+    /// The direction in which a port, or a field within it, is driven from
+    /// outside the module. Signals declared within the module have none.
+    pub fn direction(&self, id: &str, fields: &[String]) -> Option<Dir> {
+        let mut dir = self.ports.get(id)?.clone();
+        let mut ty = self.signals.get(id)?;
+        for field in fields {
+            if let Type::Interface { fields, .. } = ty.unaliased() {
+                let inner = fields.iter().find(|f| f.name == *field)?;
+                dir = dir.compose(&inner.dir);
+            }
+            ty = ty.field(field)?;
+        }
+
+        Some(dir)
+    }
+
+    /// Whether a port or signal can be shared with a bidirectional port, which
+    /// takes a wire that is either declared within the module or bidirectional
+    /// itself.
+    pub fn is_bidirectional(&self, id: &str, fields: &[String]) -> bool {
+        let wire =
+            self.reg(id).is_none() && self.memory(id).is_none() && self.constant(id).is_none();
+        wire && matches!(self.direction(id, fields), None | Some(Dir::InOut))
+    }
+
+    /// Looks up the value of a constant.
+    pub fn constant(&self, id: &str) -> Option<&Value> {
+        self.consts.get(id)
+    }
+
+    /// Looks up how a signal is clocked, if it is a register.
+    pub fn reg(&self, id: &str) -> Option<&Reg> {
+        self.regs.get(id)
+    }
+
+    /// Looks up the clock of a memory.
+    pub fn memory(&self, id: &str) -> Option<&Memory> {
+        self.mems.get(id)
+    }
+
+    /// The element type and length of the memory an expression reads from,
+    /// if it is a memory read.
+    pub fn memory_of(&self, e: &Expr) -> Option<(&Type, usize)> {
+        let id = match e {
+            Expr::Ident { id } => id,
+            Expr::Index { ex, .. } => match &**ex {
+                Expr::Ident { id } => id,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        self.memory(id)?;
+        match self.signals.get(id)?.unaliased() {
+            Type::Array { elem, len } => Some((elem, *len)),
+            _ => None,
+        }
+    }
+
+    /// Checks that an address selects a word within a memory of `len` words.
+    fn check_address(&self, addr: &Expr, len: usize) -> Result<(), Error> {
+        let ty = self.type_of(addr)?;
+        if !matches!(
+            ty.unaliased(),
+            Type::Bit | Type::Bits { .. } | Type::UInt { .. }
+        ) {
+            return Err(Error::InvalidAddress { ty, pos: self.pos });
+        }
+        match self.const_index(addr) {
+            Some(idx) => check_bounds(idx, len, self.pos),
+            None => Ok(()),
+        }
+    }
+
+    /// Evaluates the initial contents of a memory, if it is initialized from
+    /// a constant.
+    pub fn eval_array(&self, e: &Expr, elem: &Type, len: usize) -> Option<Vec<Value>> {
+        eval_array(e, &self.consts, elem, len, self.pos).ok()
+    }
+
+    /// Computes the type of an expression.
+    pub fn type_of(&self, e: &Expr) -> Result<Type, Error> {
+        match e {
+            Expr::Binary { lex, op, rex, .. } => match op {
+                // enums can only be compared for equality
+                _ if self.enum_operand_type(lex, rex)?.is_some() => match op {
+                    BinaryOp::Equal | BinaryOp::NotEqual => Ok(Type::Bit),
+                    _ => Err(Error::EnumOperation {
+                        ty: self.type_of(lex)?,
+                        pos: self.pos,
+                    }),
+                },
+                BinaryOp::ShiftLeft | BinaryOp::ShiftRight => {
+                    self.type_of(rex)?;
+                    self.type_of(lex)
+                }
+                BinaryOp::Equal
+                | BinaryOp::NotEqual
+                | BinaryOp::Less
+                | BinaryOp::LessEqual
+                | BinaryOp::Greater
+                | BinaryOp::GreaterEqual => {
+                    self.operand_type(lex, rex)?;
+                    Ok(Type::Bit)
+                }
+                BinaryOp::LogicAnd | BinaryOp::LogicOr => {
+                    self.expect_bit(lex)?;
+                    self.expect_bit(rex)?;
+                    Ok(Type::Bit)
+                }
+                BinaryOp::BitAnd
+                | BinaryOp::BitXor
+                | BinaryOp::BitOr
+                | BinaryOp::Add
+                | BinaryOp::Sub
+                | BinaryOp::Mul => {
+                    let ty = self.operand_type(lex, rex)?;
+                    let width = match op {
+                        BinaryOp::Add | BinaryOp::Sub => ty.width() + 1,
+                        BinaryOp::Mul => self.type_of(lex)?.width() + self.type_of(rex)?.width(),
+                        _ => ty.width(),
+                    };
+                    Ok(resize(&ty, width))
+                }
+                BinaryOp::Div | BinaryOp::Rem => Err(Error::NonConstantDivision { pos: self.pos }),
+            },
+            Expr::Unary { op, ex } => {
+                let ty = self.type_of(ex)?;
+                if ty.is_enum() {
+                    return Err(Error::EnumOperation { ty, pos: self.pos });
+                }
+                match op {
+                    UnaryOp::Negate => Ok(ty),
+                    UnaryOp::LogicNot => {
+                        self.expect_bit(ex)?;
+                        Ok(Type::Bit)
+                    }
+                    UnaryOp::ReductAnd | UnaryOp::ReductXor | UnaryOp::ReductOr => Ok(Type::Bit),
+                }
+            }
+            Expr::Paren { ex } => self.type_of(ex),
+            Expr::If { cond, tex, fex } => {
+                self.expect_bit(cond)?;
+                self.branch_type(&[tex, fex])
+            }
+            Expr::Match { ex, arms } => {
+                let ty = self.type_of(ex)?;
+                let mut values = HashSet::new();
+                let mut wildcard = false;
+                for pat in arms.iter().flat_map(|arm| &arm.pats) {
+                    match pat {
+                        Pat::Wildcard => wildcard = true,
+                        Pat::Litrl { val, width } if ty.is_enum() => {
+                            return Err(Error::MismatchedEnum {
+                                lty: ty,
+                                rty: Type::UInt {
+                                    width: width.unwrap_or_else(|| val.bits().max(1)),
+                                },
+                                pos: self.pos,
+                            });
+                        }
+                        Pat::Variant { ty: name, variant } => {
+                            let pty = self.variant_type(name, variant)?;
+                            if pty != ty {
+                                return Err(Error::MismatchedEnum {
+                                    lty: ty,
+                                    rty: pty,
+                                    pos: self.pos,
+                                });
+                            }
+                            values.insert(self.enums[name].value(variant).unwrap());
+                        }
+                        Pat::Litrl { val, width } => {
+                            if width.unwrap_or(0).max(val.bits()) > ty.width() {
+                                return Err(Error::PatternOutOfRange {
+                                    val: val.clone(),
+                                    width: ty.width(),
+                                    pos: self.pos,
+                                });
+                            }
+                            values.insert(val.clone());
+                        }
+                    }
+                }
+                let count = match ty.unaliased() {
+                    Type::Enum { name, .. } => BigUint::from(self.enums[name].variants.len()),
+                    _ => BigUint::one() << ty.width(),
+                };
+                if !wildcard && BigUint::from(values.len()) != count {
+                    return Err(Error::NonExhaustiveMatch { pos: self.pos });
+                }
+
+                let exs: Vec<&Expr> = arms.iter().map(|arm| &arm.ex).collect();
+                self.branch_type(&exs)
+            }
+            Expr::Index { idx, .. } if self.memory_of(e).is_some() => {
+                let (elem, len) = self.memory_of(e).unwrap();
+                self.check_address(idx, len)?;
+                Ok(elem.clone())
+            }
+            Expr::Array { .. } => Err(Error::UnexpectedArray { pos: self.pos }),
+            Expr::Index { ex, idx } => {
+                let width = self.indexable_width(ex)?;
+                self.type_of(idx)?;
+                if let Some(idx) = self.const_index(idx) {
+                    check_bounds(idx, width, self.pos)?;
+                }
+                Ok(Type::Bit)
+            }
+            Expr::Slice { ex, msb, lsb } => {
+                let width = self.indexable_width(ex)?;
+                let (msb, lsb) = match (self.const_index(msb), self.const_index(lsb)) {
+                    (Some(msb), Some(lsb)) => (msb, lsb),
+                    _ => return Err(Error::NonConstantSlice { pos: self.pos }),
+                };
+                check_bounds(msb, width, self.pos)?;
+                if msb < lsb {
+                    return Err(Error::ReversedSlice {
+                        msb,
+                        lsb,
+                        pos: self.pos,
+                    });
+                }
+                Ok(Type::Bits {
+                    width: msb - lsb + 1,
+                })
+            }
+            Expr::Concat { exs } => {
+                let mut width = 0;
+                for ex in exs {
+                    if self.is_unsized(ex) {
+                        return Err(Error::UnsizedConcatOperand { pos: self.pos });
+                    }
+                    width += self.bits_width(ex)?;
+                }
+                Ok(Type::Bits { width })
+            }
+            Expr::Repeat { count, ex } => {
+                let count = self
+                    .const_index(count)
+                    .ok_or(Error::NonConstantRepeat { pos: self.pos })?;
+                if count == 0 {
+                    return Err(Error::ZeroRepeat { pos: self.pos });
+                }
+                Ok(Type::Bits {
+                    width: count * self.bits_width(ex)?,
+                })
+            }
+            Expr::Cross { ex } => self.type_of(ex),
+            Expr::Tristate { .. } => Err(Error::UnexpectedTristate { pos: self.pos }),
+            Expr::Call { .. } => unreachable!("calls should be inlined before typing"),
+            Expr::Field { ex, field } => {
+                // interfaces aren't data themselves, but their fields may be
+                let ty = match &**ex {
+                    Expr::Ident { id } => self.lookup(id)?.clone(),
+                    ex => self.type_of(ex)?,
+                };
+                match ty.field(field) {
+                    Some(fty) => Ok(fty.clone()),
+                    None => Err(Error::UnknownField {
+                        ty,
+                        field: field.clone(),
+                        pos: self.pos,
+                    }),
+                }
+            }
+            Expr::Variant { ty, variant } => self.variant_type(ty, variant),
+            Expr::Ident { id } => match self.lookup(id)? {
+                ty if !ty.is_data() => Err(Error::ExpectedData {
+                    id: id.clone(),
+                    ty: ty.clone(),
+                    pos: self.pos,
+                }),
+                ty => Ok(ty.clone()),
+            },
+            Expr::Litrl { val, width } => Ok(Type::UInt {
+                width: width.unwrap_or_else(|| val.bits().max(1)),
+            }),
+        }
+    }
+
+    /// Computes the common type both operands of a binary operator are
+    /// evaluated at, which is as wide as the wider of the two.
+    pub fn operand_type(&self, lex: &Expr, rex: &Expr) -> Result<Type, Error> {
+        if let Some(ty) = self.enum_operand_type(lex, rex)? {
+            return Ok(ty);
+        }
+        let lty = self.type_of(lex)?;
+        let rty = self.type_of(rex)?;
+        let width = lty.width().max(rty.width());
+        let ty = if self.is_unsized(lex) {
+            rty
+        } else if self.is_unsized(rex) {
+            lty
+        } else if lty.is_signed() != rty.is_signed() {
+            return Err(Error::MismatchedSignedness {
+                lty,
+                rty,
+                pos: self.pos,
+            });
+        } else {
+            lty
+        };
+        let ty = resize(&ty, width);
+
+        // Unsized values take on the signedness of the other operand, so they
+        // must fit beneath its sign bit.
+        if ty.is_signed() {
+            let max = BigInt::one() << (width - 1);
+            for ex in [lex, rex] {
+                match self.eval(ex) {
+                    Some(v) if self.is_unsized(ex) && (v.val >= max || v.val < -&max) => {
+                        return Err(Error::LiteralOutOfRange {
+                            val: v.val,
+                            ty,
+                            pos: self.pos,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(ty)
+    }
+
+    /// Computes the type of a selection between several expressions, which
+    /// must all share a width and signedness. Unsized literals only need to
+    /// fit within that width.
+    pub fn branch_type(&self, exs: &[&Expr]) -> Result<Type, Error> {
+        let tys = exs
+            .iter()
+            .map(|ex| self.type_of(ex))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(ty) = tys.iter().find(|ty| ty.is_enum()) {
+            return match tys.iter().find(|rty| *rty != ty) {
+                Some(rty) => Err(Error::MismatchedEnum {
+                    lty: ty.clone(),
+                    rty: rty.clone(),
+                    pos: self.pos,
+                }),
+                None => Ok(ty.clone()),
+            };
+        }
+
+        let mut sized: Option<Type> = None;
+        let mut litrl = Type::UInt { width: 1 };
+        for ex in exs {
+            let ty = self.type_of(ex)?;
+            if self.is_unsized(ex) {
+                litrl = resize(&litrl, ty.width());
+                continue;
+            }
+            match &sized {
+                Some(lty) if lty.width() != ty.width() => {
+                    return Err(Error::MismatchedWidth {
+                        lty: lty.clone(),
+                        rty: ty,
+                        pos: self.pos,
+                    });
+                }
+                Some(lty) if lty.is_signed() != ty.is_signed() => {
+                    return Err(Error::MismatchedSignedness {
+                        lty: lty.clone(),
+                        rty: ty,
+                        pos: self.pos,
+                    });
+                }
+                Some(_) => {}
+                None => sized = Some(ty),
+            }
+        }
+
+        match sized {
+            Some(ty) if ty.width() < litrl.width() => Err(Error::MismatchedWidth {
+                lty: ty,
+                rty: litrl,
+                pos: self.pos,
+            }),
+            Some(ty) => Ok(ty),
+            None => Ok(litrl),
+        }
+    }
+
+    /// Whether an expression is built only from literals and constants.
+    pub fn is_constant(&self, e: &Expr) -> bool {
+        match e {
+            Expr::Binary { lex, rex, .. } => self.is_constant(lex) && self.is_constant(rex),
+            Expr::Unary { ex, .. } | Expr::Paren { ex } | Expr::Repeat { ex, .. } => {
+                self.is_constant(ex)
+            }
+            Expr::Concat { exs } => exs.iter().all(|ex| self.is_constant(ex)),
+            Expr::Ident { id } => self.constant(id).is_some(),
+            Expr::Litrl { .. } | Expr::Variant { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Unsized literals, and constants declared without a type, take on the
+    /// type of whatever they are combined with.
+    pub fn is_unsized(&self, e: &Expr) -> bool {
+        match e {
+            Expr::Paren { ex } => self.is_unsized(ex),
+            Expr::Ident { id } => self.constant(id).is_some_and(|v| v.width.is_none()),
+            Expr::Litrl { width, .. } => width.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Evaluates an expression, if it is constant.
+    pub fn eval(&self, e: &Expr) -> Option<Value> {
+        if !self.is_constant(e) {
+            return None;
+        }
+        eval(e, &self.consts, self.pos).ok()
+    }
+
+    /// Evaluates a constant index, slice bound or repeat count, if it is one.
+    pub fn const_index(&self, e: &Expr) -> Option<usize> {
+        self.eval(e)?.val.to_usize()
+    }
+
+    fn expect_bit(&self, e: &Expr) -> Result<(), Error> {
+        let ty = self.type_of(e)?;
+        if ty.width() != 1 {
+            return Err(Error::ExpectedBit { ty, pos: self.pos });
+        }
+
+        Ok(())
+    }
+
+    fn indexable_width(&self, e: &Expr) -> Result<usize, Error> {
+        let ty = self.type_of(e)?;
+        match ty.unaliased() {
+            Type::Bit | Type::Enum { .. } => Err(Error::NotIndexable { ty, pos: self.pos }),
+            _ => Ok(ty.width()),
+        }
+    }
+
+    /// The width of a concatenation operand, which can't be an enum.
+    fn bits_width(&self, e: &Expr) -> Result<usize, Error> {
+        let ty = self.type_of(e)?;
+        match ty.unaliased() {
+            Type::Enum { .. } => Err(Error::EnumOperation { ty, pos: self.pos }),
+            _ => Ok(ty.width()),
+        }
+    }
+
+    /// The common type of two operands if either is an enum, in which case
+    /// both must be of the same enum.
+    fn enum_operand_type(&self, lex: &Expr, rex: &Expr) -> Result<Option<Type>, Error> {
+        let lty = self.type_of(lex)?;
+        let rty = self.type_of(rex)?;
+        if !lty.is_enum() && !rty.is_enum() {
+            return Ok(None);
+        }
+        if lty != rty {
+            return Err(Error::MismatchedEnum {
+                lty,
+                rty,
+                pos: self.pos,
+            });
+        }
+
+        Ok(Some(lty))
+    }
+
+    /// Looks up the type of an enum variant.
+    fn variant_type(&self, ty: &str, variant: &str) -> Result<Type, Error> {
+        let e = self.enums.get(ty).ok_or_else(|| Error::UndeclaredType {
+            name: ty.to_string(),
+        })?;
+        if !e.variants.iter().any(|v| v == variant) {
+            return Err(Error::UnknownVariant {
+                ty: ty.to_string(),
+                variant: variant.to_string(),
+                pos: self.pos,
+            });
+        }
+
+        Ok(e.ty())
+    }
+}
+
+fn check_bounds(idx: usize, width: usize, pos: BytePos) -> Result<(), Error> {
+    if idx >= width {
+        return Err(Error::IndexOutOfBounds { idx, width, pos });
+    }
+
+    Ok(())
+}
+
+/// Widens a type to at least `width` bits, keeping its kind.
+fn resize(ty: &Type, width: usize) -> Type {
+    let width = width.max(ty.width());
+    match ty.unaliased() {
+        Type::Bit if width == 1 => Type::Bit,
+        Type::Bit
+        | Type::Bits { .. }
+        | Type::Clock
+        | Type::Reset
+        | Type::AsyncReset
+        | Type::Generic { .. } => Type::Bits { width },
+        Type::Named { .. }
+        | Type::Enum { .. }
+        | Type::Struct { .. }
+        | Type::Interface { .. }
+        | Type::Flipped { .. }
+        | Type::Array { .. }
+        | Type::GenericArray { .. } => ty.clone(),
+        Type::Alias { .. } => unreachable!(),
+        Type::UInt { .. } => Type::UInt { width },
+        Type::SInt { .. } => Type::SInt { width },
+    }
+}
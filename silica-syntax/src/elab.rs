@@ -0,0 +1,901 @@
+use std::collections::{HashMap, HashSet};
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use crate::ast::*;
+use crate::check::{Error, Scope};
+use crate::eval::{declare_const, eval, file_env, fit, resolve_type, to_expr, Env, Value};
+use crate::BytePos;
+
+/// The most passes a single loop may be unrolled into, which keeps a stray
+/// bound from exhausting memory.
+const MAX_UNROLL: usize = 1 << 16;
+
+/// Resolves the parameters of every module, producing one concrete module
+/// per unique set of parameter values in use. Modules used with their default
+/// values keep their name, while any others are named after their values.
+pub fn monomorphize(ast: &Ast) -> Result<Ast, Error> {
+    let mut elab = Elab {
+        ast,
+        file: file_env(ast)?,
+        mods: Vec::new(),
+    };
+    for m in &ast.mods {
+        let outer = elab.file.clone();
+        elab.instantiate(m, &m.name, &[], &outer)?;
+    }
+
+    Ok(Ast {
+        uses: ast.uses.clone(),
+        consts: ast.consts.clone(),
+        enums: ast.enums.clone(),
+        structs: ast.structs.clone(),
+        interfaces: ast.interfaces.clone(),
+        fns: ast.fns.clone(),
+        aliases: ast.aliases.clone(),
+        mods: elab.mods,
+    })
+}
+
+/// Resolves the types and constants of a module using the default values of
+/// its parameters. Instances are left untouched.
+pub fn resolve_defaults(ast: &Ast, m: &Mod) -> Result<Mod, Error> {
+    let file = file_env(ast)?;
+    let (_, env) = param_values(m, &m.name, &[], &file, &file)?;
+    let (resolved, _) = resolve_mod(ast, m, env)?;
+
+    Ok(resolved)
+}
+
+/// Unrolls the generation blocks of a module using the default values of its
+/// parameters and inlines its function calls, leaving its types and constants
+/// as declared.
+pub fn expand_defaults(ast: &Ast, m: &Mod) -> Result<Vec<Stmt>, Error> {
+    let file = file_env(ast)?;
+    let (_, mut env) = param_values(m, &m.name, &[], &file, &file)?;
+    for stmt in &m.stmts {
+        if let Stmt::Const(c) = stmt {
+            declare_const(&mut env, c)?;
+        }
+    }
+
+    let stmts = unroll(&m.stmts, &env, None)?;
+    inline(ast, &m.ports, stmts)
+}
+
+struct Elab<'a> {
+    ast: &'a Ast,
+    file: Env,
+    mods: Vec<Mod>,
+}
+
+impl<'a> Elab<'a> {
+    /// Elaborates a module with the parameter overrides of instance `id`,
+    /// returning the name of the resulting concrete module.
+    fn instantiate(
+        &mut self,
+        m: &Mod,
+        id: &str,
+        overrides: &[Conn],
+        outer: &Env,
+    ) -> Result<String, Error> {
+        let (values, env) = param_values(m, id, overrides, outer, &self.file)?;
+        let (defaults, _) = param_values(m, id, &[], outer, &self.file)?;
+        let name = if values == defaults {
+            m.name.clone()
+        } else {
+            let values: Vec<String> = values
+                .iter()
+                .map(|(name, val)| format!("{}_{}", name, val))
+                .collect();
+            let name = format!("{}_{}", m.name, values.join("_"));
+            // The variant mustn't stand in for a module declared by that name.
+            if let Some(declared) = self.ast.module(&name) {
+                return Err(Error::DuplicateModule {
+                    name,
+                    pos: declared.pos,
+                    prev: m.pos,
+                });
+            }
+            name
+        };
+        if self.mods.iter().any(|r| r.name == name) {
+            return Ok(name);
+        }
+
+        // Claim the name before elaborating instances, so the module is listed
+        // ahead of any variants it introduces.
+        let (mut resolved, env) = resolve_mod(self.ast, m, env)?;
+        resolved.name = name.clone();
+        resolved.params.clear();
+        let idx = self.mods.len();
+        self.mods.push(resolved.clone());
+
+        for stmt in &mut resolved.stmts {
+            if let Stmt::Inst {
+                id,
+                module,
+                params,
+                pos,
+                ..
+            } = stmt
+            {
+                let child = self
+                    .ast
+                    .module(module)
+                    .ok_or_else(|| Error::UndeclaredModule {
+                        name: module.clone(),
+                        pos: *pos,
+                    })?;
+                *module = self.instantiate(child, id, params, &env)?;
+                params.clear();
+            }
+        }
+        self.mods[idx] = resolved;
+
+        Ok(name)
+    }
+}
+
+/// Computes the parameter values of a module in declaration order, taking
+/// overrides from instance `id`. Overrides are evaluated in the environment
+/// of the instantiating module, while defaults may refer to file constants
+/// and earlier parameters.
+fn param_values(
+    m: &Mod,
+    id: &str,
+    overrides: &[Conn],
+    outer: &Env,
+    file: &Env,
+) -> Result<(Vec<(String, BigInt)>, Env), Error> {
+    for (idx, conn) in overrides.iter().enumerate() {
+        if !m.params.iter().any(|p| p.name == conn.port) {
+            return Err(Error::UnknownParam {
+                id: id.to_string(),
+                param: conn.port.clone(),
+                pos: conn.pos,
+            });
+        }
+        if overrides[..idx].iter().any(|c| c.port == conn.port) {
+            return Err(Error::DuplicateParam {
+                id: id.to_string(),
+                param: conn.port.clone(),
+                pos: conn.pos,
+            });
+        }
+    }
+
+    let mut values = Vec::new();
+    let mut env = file.clone();
+    for param in &m.params {
+        let val = match overrides.iter().find(|c| c.port == param.name) {
+            Some(conn) => fit(eval(&conn.ex, outer, conn.pos)?, None, conn.pos)?,
+            None => fit(eval(&param.default, &env, param.pos)?, None, param.pos)?,
+        };
+        if env.insert(param.name.clone(), val.clone()).is_some() {
+            return Err(Error::DuplicateDeclaration {
+                id: param.name.clone(),
+                pos: param.pos,
+            });
+        }
+        values.push((param.name.clone(), val.val));
+    }
+
+    Ok((values, env))
+}
+
+/// Replaces every generic type within a module by a sized one, and every
+/// constant by its value. Returns the module along with the environment of
+/// its body.
+fn resolve_mod(ast: &Ast, m: &Mod, mut env: Env) -> Result<(Mod, Env), Error> {
+    let mut resolved = m.clone();
+
+    // Constants come first, since port widths may depend on them.
+    for stmt in &mut resolved.stmts {
+        if let Stmt::Const(c) = stmt {
+            c.ty = match &c.ty {
+                Some(ty) => Some(resolve_type(ty, &env)?),
+                None => None,
+            };
+            let v: Value = declare_const(&mut env, c)?;
+            c.ex = to_expr(&v);
+        }
+    }
+    let stmts = unroll(&resolved.stmts, &env, None)?;
+    resolved.stmts = inline(ast, &resolved.ports, stmts)?;
+
+    // Parameters used within expressions are replaced by their values, so
+    // the module stands alone once its parameters are dropped.
+    let params: Env = m
+        .params
+        .iter()
+        .map(|p| (p.name.clone(), env[&p.name].clone()))
+        .collect();
+    for stmt in &mut resolved.stmts {
+        substitute(stmt, &env, &params)?;
+    }
+    for port in &mut resolved.ports {
+        port.ty = resolve_named(ast, &port.ty, &env)?;
+    }
+    for stmt in &mut resolved.stmts {
+        match stmt {
+            Stmt::Declare { ty, .. }
+            | Stmt::DeclareAssign { ty, .. }
+            | Stmt::Reg { ty, .. }
+            | Stmt::Mem { ty, .. } => {
+                *ty = resolve_named(ast, ty, &env)?;
+            }
+            Stmt::Const(_)
+            | Stmt::Assign { .. }
+            | Stmt::Inst { .. }
+            | Stmt::MemWrite { .. }
+            | Stmt::Assert { .. } => {}
+            Stmt::For { .. } | Stmt::If { .. } => unreachable!("generation blocks are unrolled"),
+        }
+    }
+
+    // Parameters share a namespace with signals once emitted, which the scope
+    // checks as it declares them.
+    Scope::new(ast, &resolved)?;
+
+    Ok((resolved, env))
+}
+
+/// Replaces a generic or named type by the type it stands for, resolving the
+/// fields of structs and interfaces as well.
+pub fn resolve_named(ast: &Ast, ty: &Type, env: &Env) -> Result<Type, Error> {
+    let name = match ty {
+        Type::Named { name } => name,
+        Type::Flipped { ty } => {
+            let resolved = resolve_named(ast, ty, env)?;
+            return match resolved.unaliased() {
+                Type::Interface { name, fields } => Ok(Type::Interface {
+                    name: name.clone(),
+                    fields: fields
+                        .iter()
+                        .map(|f| Port {
+                            dir: f.dir.flipped(),
+                            ..f.clone()
+                        })
+                        .collect(),
+                }),
+                _ => Err(Error::ExpectedInterface { ty: resolved }),
+            };
+        }
+        Type::Alias { name, ty } => {
+            return Ok(Type::Alias {
+                name: name.clone(),
+                ty: Box::new(resolve_named(ast, ty, env)?),
+            });
+        }
+        Type::Array { .. } | Type::GenericArray { .. } => {
+            return match resolve_type(ty, env)? {
+                Type::Array { elem, len } => Ok(Type::Array {
+                    elem: Box::new(resolve_named(ast, &elem, env)?),
+                    len,
+                }),
+                ty => Ok(ty),
+            };
+        }
+        ty => return resolve_type(ty, env),
+    };
+    if let Some(e) = ast.enumeration(name) {
+        return Ok(e.ty());
+    }
+    if let Some(i) = ast.interface(name) {
+        let fields = i
+            .fields
+            .iter()
+            .map(|f| {
+                Ok(Port {
+                    dir: f.dir.clone(),
+                    name: f.name.clone(),
+                    ty: resolve_named(ast, &f.ty, env)?,
+                    attrs: vec![],
+                    pos: f.pos,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        return Ok(Type::Interface {
+            name: name.clone(),
+            fields,
+        });
+    }
+    let s = ast
+        .structure(name)
+        .ok_or_else(|| Error::UndeclaredType { name: name.clone() })?;
+    let fields = s
+        .fields
+        .iter()
+        .map(|f| {
+            Ok(Field {
+                name: f.name.clone(),
+                ty: resolve_named(ast, &f.ty, env)?,
+                pos: f.pos,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(Type::Struct {
+        name: name.clone(),
+        fields,
+    })
+}
+
+/// Replaces every use of a type alias by the type it stands for, wrapped in
+/// [`Type::Alias`] so it keeps its name. Aliases are in scope from their
+/// declaration onwards among aliases, and everywhere else in the file.
+pub fn resolve_aliases(ast: &Ast) -> Ast {
+    let mut aliases = HashMap::new();
+    let mut resolved = ast.clone();
+    for alias in &mut resolved.aliases {
+        alias_type(&mut alias.ty, &aliases);
+        aliases.insert(alias.name.clone(), alias.ty.clone());
+    }
+
+    for c in &mut resolved.consts {
+        if let Some(ty) = &mut c.ty {
+            alias_type(ty, &aliases);
+        }
+    }
+    for f in resolved.structs.iter_mut().flat_map(|s| &mut s.fields) {
+        alias_type(&mut f.ty, &aliases);
+    }
+    for f in resolved.interfaces.iter_mut().flat_map(|i| &mut i.fields) {
+        alias_type(&mut f.ty, &aliases);
+    }
+    for f in &mut resolved.fns {
+        for param in &mut f.params {
+            alias_type(&mut param.ty, &aliases);
+        }
+        alias_type(&mut f.ret, &aliases);
+        alias_stmts(&mut f.body, &aliases);
+    }
+    for m in &mut resolved.mods {
+        for port in &mut m.ports {
+            alias_type(&mut port.ty, &aliases);
+        }
+        alias_stmts(&mut m.stmts, &aliases);
+    }
+
+    resolved
+}
+
+fn alias_stmts(stmts: &mut [Stmt], aliases: &HashMap<String, Type>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Declare { ty, .. }
+            | Stmt::DeclareAssign { ty, .. }
+            | Stmt::Reg { ty, .. }
+            | Stmt::Mem { ty, .. } => alias_type(ty, aliases),
+            Stmt::Const(c) => {
+                if let Some(ty) = &mut c.ty {
+                    alias_type(ty, aliases);
+                }
+            }
+            Stmt::For { body, .. } => alias_stmts(body, aliases),
+            Stmt::If { tstmts, fstmts, .. } => {
+                alias_stmts(tstmts, aliases);
+                alias_stmts(fstmts, aliases);
+            }
+            Stmt::Assign { .. }
+            | Stmt::Inst { .. }
+            | Stmt::MemWrite { .. }
+            | Stmt::Assert { .. } => {}
+        }
+    }
+}
+
+fn alias_type(ty: &mut Type, aliases: &HashMap<String, Type>) {
+    match ty {
+        Type::Named { name } => {
+            if let Some(aliased) = aliases.get(name) {
+                *ty = Type::Alias {
+                    name: name.clone(),
+                    ty: Box::new(aliased.clone()),
+                };
+            }
+        }
+        Type::Flipped { ty: elem } | Type::Array { elem, .. } | Type::GenericArray { elem, .. } => {
+            alias_type(elem, aliases)
+        }
+        _ => {}
+    }
+}
+
+/// Replaces every `for` loop by a copy of its body for each value of its
+/// variable, and every `if` block by the branch its condition selects. Names
+/// suffixed by constants are expanded along the way. `locals` holds the loop
+/// variables and constants declared within the enclosing blocks, if any,
+/// which are substituted by their values since they don't outlive the block.
+fn unroll(stmts: &[Stmt], env: &Env, locals: Option<&Env>) -> Result<Vec<Stmt>, Error> {
+    let mut env = env.clone();
+    let mut locals = locals.cloned();
+    let mut unrolled = Vec::new();
+    for stmt in stmts {
+        let block = locals.clone().unwrap_or_default();
+        match stmt {
+            Stmt::For {
+                var,
+                start,
+                end,
+                body,
+                pos,
+            } => {
+                let bound = |e: &Expr| {
+                    let v = eval(e, &env, *pos)?;
+                    v.val.to_usize().ok_or(Error::InvalidBound {
+                        pos: *pos,
+                        bound: v.val,
+                    })
+                };
+                let range = bound(start)?..bound(end)?;
+                if range.len() > MAX_UNROLL {
+                    return Err(Error::UnrollLimit {
+                        pos: *pos,
+                        count: range.len(),
+                    });
+                }
+                for i in range {
+                    let v = Value {
+                        val: BigInt::from(i),
+                        width: None,
+                    };
+                    let mut env = env.clone();
+                    if env.insert(var.clone(), v.clone()).is_some() {
+                        return Err(Error::DuplicateDeclaration {
+                            id: var.clone(),
+                            pos: *pos,
+                        });
+                    }
+                    let mut block = block.clone();
+                    block.insert(var.clone(), v);
+                    unrolled.extend(unroll(body, &env, Some(&block))?);
+                }
+            }
+            Stmt::If {
+                cond,
+                tstmts,
+                fstmts,
+                pos,
+            } => {
+                let branch = if eval(cond, &env, *pos)?.is_true() {
+                    tstmts
+                } else {
+                    fstmts
+                };
+                unrolled.extend(unroll(branch, &env, Some(&block))?);
+            }
+            // Constants within blocks take a value on every pass.
+            Stmt::Const(c) if locals.is_some() => {
+                let mut c = c.clone();
+                substitute_expr(&mut c.ex, &env, &block, c.pos)?;
+                let v = declare_const(&mut env, &c)?;
+                locals
+                    .get_or_insert_with(Env::new)
+                    .insert(c.name.clone(), v);
+            }
+            stmt => {
+                let mut stmt = stmt.clone();
+                substitute(&mut stmt, &env, &block)?;
+                unrolled.push(stmt);
+            }
+        }
+    }
+
+    Ok(unrolled)
+}
+
+/// Expands the names within a statement and substitutes the constants local
+/// to its block.
+fn substitute(stmt: &mut Stmt, env: &Env, locals: &Env) -> Result<(), Error> {
+    let pos = stmt.pos();
+    let name = |id: &mut String| -> Result<(), Error> {
+        *id = expand_name(id, env, pos)?;
+        Ok(())
+    };
+    match stmt {
+        Stmt::Assign { id, ex, .. } => {
+            name(id)?;
+            substitute_expr(ex, env, locals, pos)?;
+        }
+        Stmt::Declare { id, ty, .. } => {
+            name(id)?;
+            substitute_type(ty, env, locals, pos)?;
+        }
+        Stmt::DeclareAssign { id, ty, ex, .. } => {
+            name(id)?;
+            substitute_type(ty, env, locals, pos)?;
+            substitute_expr(ex, env, locals, pos)?;
+        }
+        Stmt::Reg { id, ty, init, .. } => {
+            name(id)?;
+            substitute_type(ty, env, locals, pos)?;
+            if let Some(init) = init {
+                substitute_expr(init, env, locals, pos)?;
+            }
+        }
+        Stmt::Inst {
+            id, params, conns, ..
+        } => {
+            name(id)?;
+            for conn in params.iter_mut().chain(conns) {
+                substitute_expr(&mut conn.ex, env, locals, pos)?;
+            }
+        }
+        Stmt::Mem { id, ty, init, .. } => {
+            name(id)?;
+            substitute_type(ty, env, locals, pos)?;
+            if let Some(MemInit::Const(ex)) = init {
+                substitute_expr(ex, env, locals, pos)?;
+            }
+        }
+        Stmt::MemWrite {
+            id, addr, ex, en, ..
+        } => {
+            name(id)?;
+            substitute_expr(addr, env, locals, pos)?;
+            substitute_expr(ex, env, locals, pos)?;
+            if let Some(en) = en {
+                substitute_expr(en, env, locals, pos)?;
+            }
+        }
+        Stmt::Const(c) => {
+            if let Some(ty) = &mut c.ty {
+                substitute_type(ty, env, locals, pos)?;
+            }
+            substitute_expr(&mut c.ex, env, locals, pos)?;
+        }
+        Stmt::Assert { ex, .. } => substitute_expr(ex, env, locals, pos)?,
+        Stmt::For { .. } | Stmt::If { .. } => unreachable!("generation blocks are unrolled"),
+    }
+
+    Ok(())
+}
+
+fn substitute_type(ty: &mut Type, env: &Env, locals: &Env, pos: BytePos) -> Result<(), Error> {
+    match ty {
+        Type::Generic { width, .. } => substitute_expr(width, env, locals, pos),
+        Type::GenericArray { elem, len, .. } => {
+            substitute_type(elem, env, locals, pos)?;
+            substitute_expr(len, env, locals, pos)
+        }
+        Type::Array { elem, .. } | Type::Flipped { ty: elem } => {
+            substitute_type(elem, env, locals, pos)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn substitute_expr(e: &mut Expr, env: &Env, locals: &Env, pos: BytePos) -> Result<(), Error> {
+    if let Expr::Ident { id } = e {
+        match locals.get(id) {
+            Some(v) => {
+                *e = Expr::Paren {
+                    ex: Box::new(to_expr(v)),
+                }
+            }
+            None => *id = expand_name(id, env, pos)?,
+        }
+        return Ok(());
+    }
+    for ex in subexprs_mut(e) {
+        substitute_expr(ex, env, locals, pos)?;
+    }
+
+    Ok(())
+}
+
+/// The expressions directly within an expression.
+fn subexprs_mut(e: &mut Expr) -> Vec<&mut Expr> {
+    match e {
+        Expr::Binary { lex, rex, .. } => vec![lex, rex],
+        Expr::Unary { ex, .. }
+        | Expr::Paren { ex }
+        | Expr::Cross { ex }
+        | Expr::Field { ex, .. } => vec![ex],
+        Expr::Tristate { en, ex } => vec![en, ex],
+        Expr::If { cond, tex, fex } => vec![cond, tex, fex],
+        Expr::Match { ex, arms } => std::iter::once(&mut **ex)
+            .chain(arms.iter_mut().map(|arm| &mut arm.ex))
+            .collect(),
+        Expr::Index { ex, idx } => vec![ex, idx],
+        Expr::Slice { ex, msb, lsb } => vec![ex, msb, lsb],
+        Expr::Concat { exs } | Expr::Array { exs } => exs.iter_mut().collect(),
+        Expr::Repeat { count, ex } => vec![count, ex],
+        Expr::Call { args, .. } => args.iter_mut().collect(),
+        Expr::Variant { .. } | Expr::Ident { .. } | Expr::Litrl { .. } => vec![],
+    }
+}
+
+/// The expressions evaluated by a statement, other than constants.
+fn stmt_exprs_mut(stmt: &mut Stmt) -> Vec<&mut Expr> {
+    match stmt {
+        Stmt::Assign { ex, .. } | Stmt::DeclareAssign { ex, .. } | Stmt::Assert { ex, .. } => {
+            vec![ex]
+        }
+        Stmt::Reg { init, .. } => init.iter_mut().collect(),
+        Stmt::Inst { params, conns, .. } => params
+            .iter_mut()
+            .chain(conns)
+            .map(|conn| &mut conn.ex)
+            .collect(),
+        Stmt::Mem { init, .. } => match init {
+            Some(MemInit::Const(ex)) => vec![ex],
+            _ => vec![],
+        },
+        Stmt::MemWrite { addr, ex, en, .. } => {
+            std::iter::once(addr).chain(Some(ex)).chain(en).collect()
+        }
+        Stmt::Const(_) | Stmt::Declare { .. } => vec![],
+        Stmt::For { .. } | Stmt::If { .. } => unreachable!("generation blocks are unrolled"),
+    }
+}
+
+/// Expands a name such as `lane#i` into `lane_3`, given `i` is 3.
+fn expand_name(id: &str, env: &Env, pos: BytePos) -> Result<String, Error> {
+    let mut parts = id.split('#');
+    let mut expanded = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let v = env.get(part).ok_or_else(|| Error::UndeclaredIdentifier {
+            id: part.to_string(),
+            pos,
+        })?;
+        let suffix = v.val.to_usize().ok_or_else(|| Error::InvalidSuffix {
+            id: id.to_string(),
+            pos,
+        })?;
+        expanded = format!("{}_{}", expanded, suffix);
+    }
+
+    Ok(expanded)
+}
+
+/// Replaces every function call within some statements by a wire declared
+/// ahead of the statement, which the inlined body of the function drives.
+fn inline(ast: &Ast, ports: &[Port], stmts: Vec<Stmt>) -> Result<Vec<Stmt>, Error> {
+    let mut inliner = Inliner::new(ast)?;
+    inliner.names.extend(ports.iter().map(|p| p.name.clone()));
+    inliner
+        .names
+        .extend(stmts.iter().filter_map(declared_name).cloned());
+
+    let mut inlined = Vec::new();
+    for stmt in stmts {
+        inliner.inline_stmt(stmt, &mut inlined)?;
+    }
+
+    Ok(inlined)
+}
+
+/// Inlines a function on its own, with its parameters taken from wires of
+/// the same name. Returns the statements computing its result.
+pub fn inline_function(ast: &Ast, f: &Function) -> Result<Vec<Stmt>, Error> {
+    let mut inliner = Inliner::new(ast)?;
+    let args = f
+        .params
+        .iter()
+        .map(|p| Expr::Ident { id: p.name.clone() })
+        .collect();
+    let mut stmts = Vec::new();
+    inliner.call(&f.name, args, f.pos, &mut stmts)?;
+
+    Ok(stmts)
+}
+
+fn declared_name(stmt: &Stmt) -> Option<&String> {
+    match stmt {
+        Stmt::Declare { id, .. }
+        | Stmt::DeclareAssign { id, .. }
+        | Stmt::Reg { id, .. }
+        | Stmt::Inst { id, .. }
+        | Stmt::Mem { id, .. } => Some(id),
+        Stmt::Const(c) => Some(&c.name),
+        _ => None,
+    }
+}
+
+struct Inliner<'a> {
+    ast: &'a Ast,
+    file: Env,
+    /// Names already taken within the module.
+    names: HashSet<String>,
+    /// Functions being inlined, innermost last.
+    stack: Vec<String>,
+}
+
+impl<'a> Inliner<'a> {
+    fn new(ast: &'a Ast) -> Result<Inliner<'a>, Error> {
+        let file = file_env(ast)?;
+        Ok(Inliner {
+            ast,
+            names: file.keys().cloned().collect(),
+            file,
+            stack: Vec::new(),
+        })
+    }
+
+    /// Inlines the calls within a statement, pushing it after the statements
+    /// computing their values.
+    fn inline_stmt(&mut self, mut stmt: Stmt, out: &mut Vec<Stmt>) -> Result<(), Error> {
+        let pos = stmt.pos();
+        for e in stmt_exprs_mut(&mut stmt) {
+            self.inline_expr(e, pos, out)?;
+        }
+        out.push(stmt);
+
+        Ok(())
+    }
+
+    fn inline_expr(
+        &mut self,
+        e: &mut Expr,
+        pos: BytePos,
+        out: &mut Vec<Stmt>,
+    ) -> Result<(), Error> {
+        for ex in subexprs_mut(e) {
+            self.inline_expr(ex, pos, out)?;
+        }
+        if let Expr::Call { name, args } = e {
+            let id = self.call(name, std::mem::take(args), pos, out)?;
+            *e = Expr::Ident { id };
+        }
+
+        Ok(())
+    }
+
+    /// Expands a call found at `pos` into a wire for every parameter and
+    /// local value of the function, each named after the call, returning the
+    /// wire holding its result.
+    fn call(
+        &mut self,
+        name: &str,
+        args: Vec<Expr>,
+        pos: BytePos,
+        out: &mut Vec<Stmt>,
+    ) -> Result<String, Error> {
+        let f = self
+            .ast
+            .function(name)
+            .ok_or_else(|| Error::UndeclaredFunction {
+                name: name.to_string(),
+                pos,
+            })?;
+        if args.len() != f.params.len() {
+            return Err(Error::MismatchedArguments {
+                name: name.to_string(),
+                expected: f.params.len(),
+                found: args.len(),
+                pos,
+            });
+        }
+        if self.stack.iter().any(|n| n == name) {
+            return Err(Error::RecursiveFunction {
+                name: name.to_string(),
+                pos,
+            });
+        }
+
+        // The result is computed like any other local value, under a name
+        // none of them can take.
+        let mut body = f.body.clone();
+        body.push(Stmt::DeclareAssign {
+            id: String::new(),
+            ty: f.ret.clone(),
+            ex: f.result.clone(),
+            attrs: vec![],
+            pos: f.pos,
+        });
+        let body = unroll(&body, &self.file, Some(&Env::new()))?;
+
+        let mut locals: HashSet<String> = HashSet::new();
+        for (id, pos) in f
+            .params
+            .iter()
+            .map(|p| (&p.name, p.pos))
+            .chain(body.iter().filter_map(|stmt| match stmt {
+                Stmt::Declare { id, pos, .. } | Stmt::DeclareAssign { id, pos, .. } => {
+                    Some((id, *pos))
+                }
+                _ => None,
+            }))
+        {
+            if !locals.insert(id.clone()) {
+                return Err(Error::DuplicateDeclaration {
+                    id: id.clone(),
+                    pos,
+                });
+            }
+        }
+
+        // Neither the result nor any of the locals may take a name in use.
+        let prefix = (0..)
+            .map(|n| format!("{}_{}", name, n))
+            .find(|prefix| {
+                !self.names.contains(prefix)
+                    && locals
+                        .iter()
+                        .all(|id| !self.names.contains(&format!("{}_{}", prefix, id)))
+            })
+            .unwrap();
+        let local = |id: &str| match id {
+            "" => prefix.clone(),
+            id => format!("{}_{}", prefix, id),
+        };
+        self.names.insert(prefix.clone());
+        self.names.extend(locals.iter().map(|id| local(id)));
+
+        self.stack.push(name.to_string());
+        for (param, arg) in f.params.iter().zip(args) {
+            out.push(Stmt::DeclareAssign {
+                id: local(&param.name),
+                ty: param.ty.clone(),
+                ex: arg,
+                attrs: vec![],
+                pos,
+            });
+        }
+        for mut stmt in body {
+            let pos = stmt.pos();
+            match &mut stmt {
+                Stmt::Assign { id, .. } => {
+                    if f.params.iter().any(|p| p.name == *id) {
+                        return Err(Error::AssignToInput {
+                            id: id.clone(),
+                            pos,
+                        });
+                    }
+                    if !locals.contains(id) {
+                        return Err(Error::UndeclaredIdentifier {
+                            id: id.clone(),
+                            pos,
+                        });
+                    }
+                    *id = local(id);
+                }
+                Stmt::Declare { id, .. } | Stmt::DeclareAssign { id, .. } => *id = local(id),
+                _ => {
+                    return Err(Error::StatefulFunction {
+                        name: name.to_string(),
+                        pos,
+                    });
+                }
+            }
+            for e in stmt_exprs_mut(&mut stmt) {
+                rename_locals(e, &locals, &self.file, &local, pos)?;
+            }
+            self.inline_stmt(stmt, out)?;
+        }
+        self.stack.pop();
+
+        Ok(prefix)
+    }
+}
+
+/// Renames the local values of a function within an expression. Functions
+/// are pure, so anything else referred to must be a constant.
+fn rename_locals(
+    e: &mut Expr,
+    locals: &HashSet<String>,
+    file: &Env,
+    local: &dyn Fn(&str) -> String,
+    pos: BytePos,
+) -> Result<(), Error> {
+    if let Expr::Ident { id } = e {
+        if locals.contains(id) {
+            *id = local(id);
+        } else if !file.contains_key(id) {
+            return Err(Error::UndeclaredIdentifier {
+                id: id.clone(),
+                pos,
+            });
+        }
+        return Ok(());
+    }
+    for ex in subexprs_mut(e) {
+        rename_locals(ex, locals, file, local, pos)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::ast::*;
+use crate::check::Error;
+use crate::BytePos;
+
+/// The largest shift the evaluator will perform, which keeps a stray shift
+/// amount from exhausting memory.
+const MAX_SHIFT: usize = 1 << 16;
+
+/// The widest value a repetition may produce, for the same reason.
+const MAX_REPEAT: usize = 1 << 16;
+
+/// A constant value, along with its width if it has one. Unsized values are
+/// as wide as they need to be.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Value {
+    pub val: BigInt,
+    pub width: Option<usize>,
+}
+
+/// The constants and parameters visible while evaluating an expression.
+pub type Env = HashMap<String, Value>;
+
+impl Value {
+    /// Number of bits the value occupies.
+    pub fn width(&self) -> usize {
+        self.width.unwrap_or_else(|| match self.val.sign() {
+            Sign::Minus => (-&self.val - 1u32).bits() + 1,
+            _ => self.val.bits().max(1),
+        })
+    }
+
+    /// The two's complement bit pattern of the value within its width.
+    pub fn bits(&self) -> BigUint {
+        let modulus = BigInt::one() << self.width();
+        let val = ((&self.val % &modulus) + &modulus) % &modulus;
+        val.to_biguint().unwrap()
+    }
+
+    /// Whether the value holds as a condition, which takes any nonzero value.
+    pub fn is_true(&self) -> bool {
+        !self.val.is_zero()
+    }
+
+    fn bool(b: bool) -> Value {
+        Value {
+            val: if b { BigInt::one() } else { BigInt::zero() },
+            width: Some(1),
+        }
+    }
+}
+
+/// Evaluates a constant expression found at `pos`. Arithmetic is carried
+/// out without loss, so overflow only shows up once the value is checked
+/// against the type it ends up in.
+pub fn eval(e: &Expr, env: &Env, pos: BytePos) -> Result<Value, Error> {
+    match e {
+        Expr::Binary {
+            lex,
+            op,
+            rex,
+            pos: op_pos,
+        } => {
+            let l = eval(lex, env, pos)?;
+            let r = eval(rex, env, pos)?;
+            let width = match (l.width, r.width) {
+                (None, None) => None,
+                _ => Some(l.width().max(r.width())),
+            };
+            let val = match op {
+                BinaryOp::ShiftLeft | BinaryOp::ShiftRight => {
+                    let shift = match r.val.to_usize() {
+                        Some(shift) if shift <= MAX_SHIFT => shift,
+                        _ => return Err(Error::Overflow { pos, val: r.val }),
+                    };
+                    let val = match op {
+                        BinaryOp::ShiftLeft => l.val << shift,
+                        _ => l.val >> shift,
+                    };
+                    return Ok(Value {
+                        val,
+                        width: l.width,
+                    });
+                }
+                BinaryOp::BitAnd => l.val & r.val,
+                BinaryOp::BitXor => l.val ^ r.val,
+                BinaryOp::BitOr => l.val | r.val,
+                BinaryOp::Add => l.val + r.val,
+                BinaryOp::Sub => l.val - r.val,
+                BinaryOp::Mul => l.val * r.val,
+                BinaryOp::Div | BinaryOp::Rem if r.val.is_zero() => {
+                    return Err(Error::DivisionByZero { pos: *op_pos });
+                }
+                BinaryOp::Div => l.val / r.val,
+                BinaryOp::Rem => l.val % r.val,
+                BinaryOp::Equal => return Ok(Value::bool(l.val == r.val)),
+                BinaryOp::NotEqual => return Ok(Value::bool(l.val != r.val)),
+                BinaryOp::Less => return Ok(Value::bool(l.val < r.val)),
+                BinaryOp::LessEqual => return Ok(Value::bool(l.val <= r.val)),
+                BinaryOp::Greater => return Ok(Value::bool(l.val > r.val)),
+                BinaryOp::GreaterEqual => return Ok(Value::bool(l.val >= r.val)),
+                BinaryOp::LogicAnd => return Ok(Value::bool(l.is_true() && r.is_true())),
+                BinaryOp::LogicOr => return Ok(Value::bool(l.is_true() || r.is_true())),
+            };
+            Ok(Value { val, width })
+        }
+        Expr::Unary { op, ex } => {
+            let v = eval(ex, env, pos)?;
+            let ones = (BigUint::one() << v.width()) - 1u32;
+            match op {
+                UnaryOp::Negate => Ok(Value {
+                    val: BigInt::from(&ones ^ v.bits()),
+                    width: Some(v.width()),
+                }),
+                UnaryOp::LogicNot => Ok(Value::bool(!v.is_true())),
+                UnaryOp::ReductAnd => Ok(Value::bool(v.bits() == ones)),
+                UnaryOp::ReductXor => {
+                    let ones = v.bits().to_str_radix(2).matches('1').count();
+                    Ok(Value::bool(ones % 2 == 1))
+                }
+                UnaryOp::ReductOr => Ok(Value::bool(v.is_true())),
+            }
+        }
+        Expr::Paren { ex } => eval(ex, env, pos),
+        Expr::If { cond, tex, fex } => {
+            if eval(cond, env, pos)?.is_true() {
+                eval(tex, env, pos)
+            } else {
+                eval(fex, env, pos)
+            }
+        }
+        Expr::Index { ex, idx } => {
+            let v = eval(ex, env, pos)?;
+            let idx = eval_index(idx, env, pos)?;
+            if idx >= v.width() {
+                return Err(Error::IndexOutOfBounds {
+                    idx,
+                    width: v.width(),
+                    pos,
+                });
+            }
+            Ok(Value::bool(((v.bits() >> idx) & BigUint::one()).is_one()))
+        }
+        Expr::Slice { ex, msb, lsb } => {
+            let v = eval(ex, env, pos)?;
+            let (msb, lsb) = (eval_index(msb, env, pos)?, eval_index(lsb, env, pos)?);
+            if msb >= v.width() {
+                return Err(Error::IndexOutOfBounds {
+                    idx: msb,
+                    width: v.width(),
+                    pos,
+                });
+            }
+            if msb < lsb {
+                return Err(Error::ReversedSlice { msb, lsb, pos });
+            }
+            let width = msb - lsb + 1;
+            let ones = (BigUint::one() << width) - 1u32;
+            Ok(Value {
+                val: BigInt::from((v.bits() >> lsb) & ones),
+                width: Some(width),
+            })
+        }
+        Expr::Concat { exs } => {
+            let mut val = BigUint::zero();
+            let mut width = 0;
+            for ex in exs {
+                let v = eval(ex, env, pos)?;
+                val = (val << v.width()) | v.bits();
+                width += v.width();
+            }
+            Ok(Value {
+                val: BigInt::from(val),
+                width: Some(width),
+            })
+        }
+        Expr::Repeat { count, ex } => {
+            let count = eval_index(count, env, pos)?;
+            if count == 0 {
+                return Err(Error::ZeroRepeat { pos });
+            }
+            let v = eval(ex, env, pos)?;
+            if count > MAX_REPEAT / v.width() {
+                return Err(Error::Overflow {
+                    pos,
+                    val: BigInt::from(count),
+                });
+            }
+            let mut val = BigUint::zero();
+            for _ in 0..count {
+                val = (val << v.width()) | v.bits();
+            }
+            Ok(Value {
+                val: BigInt::from(val),
+                width: Some(count * v.width()),
+            })
+        }
+        Expr::Ident { id } => env
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::UndeclaredIdentifier {
+                id: id.clone(),
+                pos,
+            }),
+        Expr::Litrl { val, width } => Ok(Value {
+            val: BigInt::from(val.clone()),
+            width: *width,
+        }),
+        Expr::Match { .. }
+        | Expr::Cross { .. }
+        | Expr::Tristate { .. }
+        | Expr::Call { .. }
+        | Expr::Field { .. }
+        | Expr::Variant { .. }
+        | Expr::Array { .. } => Err(Error::NonConstantExpression { pos }),
+    }
+}
+
+fn eval_index(e: &Expr, env: &Env, pos: BytePos) -> Result<usize, Error> {
+    let v = eval(e, env, pos)?;
+    v.val.to_usize().ok_or(Error::Overflow { pos, val: v.val })
+}
+
+/// Checks that a value fits within a type, giving it the width of that type.
+/// A missing type stands for an unbounded unsigned integer.
+pub fn fit(v: Value, ty: Option<&Type>, pos: BytePos) -> Result<Value, Error> {
+    let (min, max, width) = match ty {
+        None => (BigInt::zero(), None, None),
+        Some(ty) if !is_numeric(ty) => return Err(Error::NonConstantExpression { pos }),
+        Some(ty) if ty.is_signed() => {
+            let half = BigInt::one() << (ty.width() - 1);
+            (-&half, Some(half), Some(ty.width()))
+        }
+        Some(ty) => (
+            BigInt::zero(),
+            Some(BigInt::one() << ty.width()),
+            Some(ty.width()),
+        ),
+    };
+    if v.val < min || max.is_some_and(|max| v.val >= max) {
+        return Err(Error::Overflow { pos, val: v.val });
+    }
+
+    Ok(Value { val: v.val, width })
+}
+
+/// Evaluates the elements of a constant array of `len` elements of type
+/// `elem`. Besides an array literal, this may be any constant holding the
+/// elements packed together, element zero in the lowest bits.
+pub fn eval_array(
+    e: &Expr,
+    env: &Env,
+    elem: &Type,
+    len: usize,
+    pos: BytePos,
+) -> Result<Vec<Value>, Error> {
+    if !is_numeric(elem) {
+        return Err(Error::NonConstantExpression { pos });
+    }
+    if let Expr::Array { exs } = e {
+        if exs.len() != len {
+            return Err(Error::MismatchedLength {
+                expected: len,
+                found: exs.len(),
+                pos,
+            });
+        }
+        return exs
+            .iter()
+            .map(|ex| fit(eval(ex, env, pos)?, Some(elem), pos))
+            .collect();
+    }
+
+    let width = elem.width();
+    let packed = fit(
+        eval(e, env, pos)?,
+        Some(&Type::Bits { width: width * len }),
+        pos,
+    )?;
+    let mask = (BigUint::one() << width) - 1u32;
+    (0..len)
+        .map(|idx| {
+            let bits = (packed.bits() >> (idx * width)) & &mask;
+            let mut val = BigInt::from(bits);
+            if elem.is_signed() && val.bits() == width {
+                val -= BigInt::one() << width;
+            }
+            Ok(Value {
+                val,
+                width: Some(width),
+            })
+        })
+        .collect()
+}
+
+/// Packs the elements of an array into a single value, element zero in the
+/// lowest bits.
+pub fn pack(elems: &[Value]) -> Value {
+    let mut val = BigUint::zero();
+    let mut width = 0;
+    for v in elems.iter().rev() {
+        val = (val << v.width()) | v.bits();
+        width += v.width();
+    }
+
+    Value {
+        val: BigInt::from(val),
+        width: Some(width),
+    }
+}
+
+/// Whether constants of a type can be evaluated, which takes a bit or vector.
+fn is_numeric(ty: &Type) -> bool {
+    matches!(
+        ty.unaliased(),
+        Type::Bit | Type::Bits { .. } | Type::UInt { .. } | Type::SInt { .. }
+    )
+}
+
+/// Evaluates a width, which must be positive.
+pub fn eval_width(e: &Expr, env: &Env, pos: BytePos) -> Result<usize, Error> {
+    let v = eval(e, env, pos)?;
+    match v.val.to_usize() {
+        Some(width) if width > 0 => Ok(width),
+        _ => Err(Error::InvalidWidth { pos, width: v.val }),
+    }
+}
+
+/// Replaces a generic type by a sized one.
+pub fn resolve_type(ty: &Type, env: &Env) -> Result<Type, Error> {
+    match ty {
+        Type::Generic { kind, width, pos } => Ok(kind.sized(eval_width(width, env, *pos)?)),
+        Type::Array { elem, len } => Ok(Type::Array {
+            elem: Box::new(resolve_type(elem, env)?),
+            len: *len,
+        }),
+        Type::GenericArray { elem, len, pos } => Ok(Type::Array {
+            elem: Box::new(resolve_type(elem, env)?),
+            len: eval_width(len, env, *pos)?,
+        }),
+        Type::Alias { name, ty } => Ok(Type::Alias {
+            name: name.clone(),
+            ty: Box::new(resolve_type(ty, env)?),
+        }),
+        ty => Ok(ty.clone()),
+    }
+}
+
+/// Evaluates the constants declared at file scope, in declaration order.
+pub fn file_env(ast: &Ast) -> Result<Env, Error> {
+    let mut env = Env::new();
+    for c in &ast.consts {
+        declare_const(&mut env, c)?;
+    }
+
+    Ok(env)
+}
+
+/// Evaluates a constant declaration and adds it to `env`.
+pub fn declare_const(env: &mut Env, c: &Const) -> Result<Value, Error> {
+    let ty = match &c.ty {
+        Some(ty) => Some(resolve_type(ty, env)?),
+        None => None,
+    };
+    let v = match ty.as_ref().map(Type::unaliased) {
+        Some(Type::Array { elem, len }) => pack(&eval_array(&c.ex, env, elem, *len, c.pos)?),
+        ty => fit(eval(&c.ex, env, c.pos)?, ty, c.pos)?,
+    };
+    if env.insert(c.name.clone(), v.clone()).is_some() {
+        return Err(Error::DuplicateDeclaration {
+            id: c.name.clone(),
+            pos: c.pos,
+        });
+    }
+
+    Ok(v)
+}
+
+/// An expression that evaluates back to a value.
+pub fn to_expr(v: &Value) -> Expr {
+    let litrl = |val: BigInt| Expr::Litrl {
+        val: val.to_biguint().unwrap(),
+        width: None,
+    };
+    match v.val.sign() {
+        Sign::Minus => Expr::Binary {
+            lex: Box::new(litrl(BigInt::zero())),
+            op: BinaryOp::Sub,
+            rex: Box::new(litrl(-&v.val)),
+            pos: 0,
+        },
+        _ => litrl(v.val.clone()),
+    }
+}
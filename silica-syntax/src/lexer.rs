@@ -1,6 +1,8 @@
 use std::mem;
 use std::str::CharIndices;
 
+use num_bigint::BigUint;
+
 use crate::BytePos;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -10,15 +12,28 @@ pub enum Token {
 
     // Literals
     Litrl(String),
+    Str(String),
 
     // Punctuation
     Comma,
     Semicolon,
     Colon,
+    PathSep,
+    Dot,
+    DotDot,
+    Hash,
+    At,
     LeftParen,
     RightParen,
     LeftCurlyBrace,
     RightCurlyBrace,
+    LeftAngleBracket,
+    RightAngleBracket,
+    LeftSquareBracket,
+    RightSquareBracket,
+    FatArrow,
+    Arrow,
+    Underscore,
 
     // Operators
     Assign,
@@ -28,14 +43,54 @@ pub enum Token {
     BitXor,
     ShiftLeft,
     ShiftRight,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Equal,
+    NotEqual,
+    LessEqual,
+    GreaterEqual,
+    LogicAnd,
+    LogicOr,
+    LogicNot,
 
     // Keywords
     Mod,
     Top,
     In,
     Out,
+    InOut,
     Bit,
+    Bits,
+    UInt,
+    SInt,
+    Clock,
+    Reset,
+    AsyncReset,
     Let,
+    Const,
+    Enum,
+    Struct,
+    Interface,
+    Flipped,
+    Fn,
+    Type,
+    Use,
+    Assert,
+    Assume,
+    Cover,
+    Reg,
+    Mem,
+    ReadMemH,
+    Inst,
+    If,
+    Else,
+    For,
+    Match,
+    Cross,
+    Tristate,
 }
 
 #[derive(Debug, PartialEq)]
@@ -44,6 +99,34 @@ pub struct InvalidCharacterError {
     pub ch: char,
 }
 
+/// Parses the text of a literal token into its value and optional width.
+///
+/// Returns `None` if the width is zero or too narrow to hold the value.
+pub fn parse_literal(text: &str) -> Option<(BigUint, Option<usize>)> {
+    let (digits, width) = match text.find('w') {
+        Some(idx) => (
+            &text[..idx],
+            Some(text[idx + 1..].replace('_', "").parse().ok()?),
+        ),
+        None => (text, None),
+    };
+
+    let (digits, radix) = match digits.get(..2) {
+        Some("0x") => (&digits[2..], 16),
+        Some("0o") => (&digits[2..], 8),
+        Some("0b") => (&digits[2..], 2),
+        _ => (digits, 10),
+    };
+    let digits = digits.replace('_', "");
+    let val = BigUint::parse_bytes(digits.as_bytes(), radix)?;
+
+    match width {
+        Some(0) => None,
+        Some(width) if val.bits() > width => None,
+        _ => Some((val, width)),
+    }
+}
+
 pub type LexerItem = Result<(BytePos, Token, BytePos), InvalidCharacterError>;
 
 pub struct Lexer<'a> {
@@ -52,14 +135,11 @@ pub struct Lexer<'a> {
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &str) -> Lexer {
+    pub fn new(input: &str) -> Lexer<'_> {
         let mut chars = input.char_indices();
         let lookahead = chars.next();
 
-        Lexer {
-            chars: chars,
-            lookahead: lookahead,
-        }
+        Lexer { chars, lookahead }
     }
 
     fn read_char(&mut self) -> Option<(usize, char)> {
@@ -107,30 +187,123 @@ impl<'a> Lexer<'a> {
             "in" => Token::In,
             "out" => Token::Out,
             "bit" => Token::Bit,
+            "bits" => Token::Bits,
+            "uint" => Token::UInt,
+            "sint" => Token::SInt,
+            "clock" => Token::Clock,
+            "reset" => Token::Reset,
+            "async_reset" => Token::AsyncReset,
             "let" => Token::Let,
+            "const" => Token::Const,
+            "enum" => Token::Enum,
+            "struct" => Token::Struct,
+            "interface" => Token::Interface,
+            "Flipped" => Token::Flipped,
+            "fn" => Token::Fn,
+            "type" => Token::Type,
+            "use" => Token::Use,
+            "assert" => Token::Assert,
+            "assume" => Token::Assume,
+            "cover" => Token::Cover,
+            "reg" => Token::Reg,
+            "mem" => Token::Mem,
+            "readmemh" => Token::ReadMemH,
+            "inst" => Token::Inst,
+            "if" => Token::If,
+            "for" => Token::For,
+            "else" => Token::Else,
+            "match" => Token::Match,
+            "cross" => Token::Cross,
+            "inout" => Token::InOut,
+            "tristate" => Token::Tristate,
+            "_" => Token::Underscore,
             _ => Token::Ident(ident.to_string()),
         };
 
         (start, token, end + 1)
     }
 
-    fn read_number(&mut self, start: usize, first: char) -> (usize, Token, usize) {
-        let mut number = String::new();
-        let mut end = start;
-        number.push(first);
+    fn read_digits(&mut self, number: &mut String, end: &mut usize, radix: u32) -> bool {
+        let mut found = false;
 
         while let Some((_, ch)) = self.peek_char() {
-            if self.is_number(ch) {
+            if ch.is_digit(radix) || ch == '_' {
                 if let Some((i, ch)) = self.read_char() {
+                    found |= ch != '_';
                     number.push(ch);
-                    end = i;
+                    *end = i;
                 }
             } else {
                 break;
             }
         }
 
-        (start, Token::Litrl(number), end + 1)
+        found
+    }
+
+    fn read_number(&mut self, start: usize, first: char) -> LexerItem {
+        let mut number = String::new();
+        let mut end = start;
+        number.push(first);
+
+        let mut radix = 10;
+        if first == '0' {
+            if let Some((i, ch)) = self.peek_char() {
+                radix = match ch {
+                    'x' => 16,
+                    'o' => 8,
+                    'b' => 2,
+                    _ => 10,
+                };
+                if radix != 10 {
+                    self.read_char();
+                    number.push(ch);
+                    end = i;
+                    if !self.read_digits(&mut number, &mut end, radix) {
+                        return Err(InvalidCharacterError { pos: i, ch });
+                    }
+                }
+            }
+        }
+        if radix == 10 {
+            self.read_digits(&mut number, &mut end, radix);
+        }
+
+        if let Some((i, 'w')) = self.peek_char() {
+            self.read_char();
+            number.push('w');
+            end = i;
+            if !self.read_digits(&mut number, &mut end, 10) {
+                return Err(InvalidCharacterError { pos: i, ch: 'w' });
+            }
+        }
+
+        if let Some((i, ch)) = self.peek_char() {
+            if self.is_letter(ch) || self.is_number(ch) {
+                return Err(InvalidCharacterError { pos: i, ch });
+            }
+        }
+
+        Ok((start, Token::Litrl(number), end + 1))
+    }
+
+    /// Reads a string up to its closing quote. Strings hold file paths, so
+    /// there are no escapes, and they can't span lines.
+    fn read_string(&mut self, start: usize) -> LexerItem {
+        let mut string = String::new();
+
+        while let Some((i, ch)) = self.read_char() {
+            match ch {
+                '"' => return Ok((start, Token::Str(string), i + 1)),
+                '\n' => break,
+                ch => string.push(ch),
+            }
+        }
+
+        Err(InvalidCharacterError {
+            pos: start,
+            ch: '"',
+        })
     }
 
     fn skip_whitespace(&mut self) {
@@ -159,30 +332,99 @@ impl<'a> Iterator for Lexer<'a> {
             match ch {
                 ',' => Some(Ok((i, Token::Comma, i + 1))),
                 ';' => Some(Ok((i, Token::Semicolon, i + 1))),
-                ':' => Some(Ok((i, Token::Colon, i + 1))),
+                ':' => {
+                    if self.peek_char_eq(':') {
+                        self.read_char();
+                        Some(Ok((i, Token::PathSep, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::Colon, i + 1)))
+                    }
+                }
+                '.' => {
+                    if self.peek_char_eq('.') {
+                        self.read_char();
+                        Some(Ok((i, Token::DotDot, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::Dot, i + 1)))
+                    }
+                }
+                '#' => Some(Ok((i, Token::Hash, i + 1))),
+                '@' => Some(Ok((i, Token::At, i + 1))),
+                '"' => Some(self.read_string(i)),
                 '(' => Some(Ok((i, Token::LeftParen, i + 1))),
                 ')' => Some(Ok((i, Token::RightParen, i + 1))),
                 '{' => Some(Ok((i, Token::LeftCurlyBrace, i + 1))),
                 '}' => Some(Ok((i, Token::RightCurlyBrace, i + 1))),
-                '=' => Some(Ok((i, Token::Assign, i + 1))),
+                '[' => Some(Ok((i, Token::LeftSquareBracket, i + 1))),
+                ']' => Some(Ok((i, Token::RightSquareBracket, i + 1))),
+                '=' => {
+                    if self.peek_char_eq('=') {
+                        self.read_char();
+                        Some(Ok((i, Token::Equal, i + 2)))
+                    } else if self.peek_char_eq('>') {
+                        self.read_char();
+                        Some(Ok((i, Token::FatArrow, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::Assign, i + 1)))
+                    }
+                }
+                '!' => {
+                    if self.peek_char_eq('=') {
+                        self.read_char();
+                        Some(Ok((i, Token::NotEqual, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::LogicNot, i + 1)))
+                    }
+                }
                 '~' => Some(Ok((i, Token::Negate, i + 1))),
-                '&' => Some(Ok((i, Token::BitAnd, i + 1))),
-                '|' => Some(Ok((i, Token::BitOr, i + 1))),
+                '&' => {
+                    if self.peek_char_eq('&') {
+                        self.read_char();
+                        Some(Ok((i, Token::LogicAnd, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::BitAnd, i + 1)))
+                    }
+                }
+                '|' => {
+                    if self.peek_char_eq('|') {
+                        self.read_char();
+                        Some(Ok((i, Token::LogicOr, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::BitOr, i + 1)))
+                    }
+                }
                 '^' => Some(Ok((i, Token::BitXor, i + 1))),
+                '+' => Some(Ok((i, Token::Plus, i + 1))),
+                '-' => {
+                    if self.peek_char_eq('>') {
+                        self.read_char();
+                        Some(Ok((i, Token::Arrow, i + 2)))
+                    } else {
+                        Some(Ok((i, Token::Minus, i + 1)))
+                    }
+                }
+                '*' => Some(Ok((i, Token::Star, i + 1))),
+                '%' => Some(Ok((i, Token::Percent, i + 1))),
                 '<' => {
                     if self.peek_char_eq('<') {
                         self.read_char();
                         Some(Ok((i, Token::ShiftLeft, i + 2)))
+                    } else if self.peek_char_eq('=') {
+                        self.read_char();
+                        Some(Ok((i, Token::LessEqual, i + 2)))
                     } else {
-                        Some(Err(InvalidCharacterError { pos: i, ch: '<' }))
+                        Some(Ok((i, Token::LeftAngleBracket, i + 1)))
                     }
                 }
                 '>' => {
                     if self.peek_char_eq('>') {
                         self.read_char();
                         Some(Ok((i, Token::ShiftRight, i + 2)))
+                    } else if self.peek_char_eq('=') {
+                        self.read_char();
+                        Some(Ok((i, Token::GreaterEqual, i + 2)))
                     } else {
-                        Some(Err(InvalidCharacterError { pos: i, ch: '>' }))
+                        Some(Ok((i, Token::RightAngleBracket, i + 1)))
                     }
                 }
                 '/' => {
@@ -190,16 +432,16 @@ impl<'a> Iterator for Lexer<'a> {
                         self.skip_line();
                         self.next()
                     } else {
-                        Some(Err(InvalidCharacterError { pos: i, ch: '/' }))
+                        Some(Ok((i, Token::Slash, i + 1)))
                     }
                 }
-                ch @ _ => {
+                ch => {
                     if self.is_letter(ch) {
                         Some(Ok(self.read_identifier(i, ch)))
                     } else if self.is_number(ch) {
-                        Some(Ok(self.read_number(i, ch)))
+                        Some(self.read_number(i, ch))
                     } else {
-                        Some(Err(InvalidCharacterError { pos: i, ch: ch }))
+                        Some(Err(InvalidCharacterError { pos: i, ch }))
                     }
                 }
             }
@@ -247,6 +489,74 @@ mod test {
         );
     }
 
+    #[test]
+    fn radix_literal() {
+        assert_lex(
+            "0xFF 0o17 0b1010_0101 1_000 0x1w12",
+            vec![
+                Ok((0, Token::Litrl("0xFF".to_string()), 4)),
+                Ok((5, Token::Litrl("0o17".to_string()), 9)),
+                Ok((10, Token::Litrl("0b1010_0101".to_string()), 21)),
+                Ok((22, Token::Litrl("1_000".to_string()), 27)),
+                Ok((28, Token::Litrl("0x1w12".to_string()), 34)),
+            ],
+        );
+    }
+
+    #[test]
+    fn invalid_literal() {
+        let mut lexer = Lexer::new("0b102");
+        assert_eq!(
+            lexer.next(),
+            Some(Err(InvalidCharacterError { pos: 4, ch: '2' }))
+        );
+
+        let mut lexer = Lexer::new("0x;");
+        assert_eq!(
+            lexer.next(),
+            Some(Err(InvalidCharacterError { pos: 1, ch: 'x' }))
+        );
+
+        let mut lexer = Lexer::new("12w");
+        assert_eq!(
+            lexer.next(),
+            Some(Err(InvalidCharacterError { pos: 2, ch: 'w' }))
+        );
+    }
+
+    #[test]
+    fn strings() {
+        assert_lex(
+            "\"init.hex\" \"\"",
+            vec![
+                Ok((0, Token::Str("init.hex".to_string()), 10)),
+                Ok((11, Token::Str("".to_string()), 13)),
+            ],
+        );
+
+        let mut lexer = Lexer::new("\"init.hex\n\"");
+        assert_eq!(
+            lexer.next(),
+            Some(Err(InvalidCharacterError { pos: 0, ch: '"' }))
+        );
+    }
+
+    #[test]
+    fn parse_literals() {
+        assert_eq!(parse_literal("0123"), Some((BigUint::from(123u32), None)));
+        assert_eq!(
+            parse_literal("0xFFw8"),
+            Some((BigUint::from(255u32), Some(8)))
+        );
+        assert_eq!(parse_literal("0o1_7"), Some((BigUint::from(15u32), None)));
+        assert_eq!(
+            parse_literal("0b101w3"),
+            Some((BigUint::from(5u32), Some(3)))
+        );
+        assert_eq!(parse_literal("0x100w8"), None);
+        assert_eq!(parse_literal("0w0"), None);
+    }
+
     #[test]
     fn comments() {
         assert_lex(
@@ -258,14 +568,42 @@ mod test {
     #[test]
     fn keywords() {
         assert_lex(
-            "mod top in out bit let",
+            "mod top in out bit bits uint sint let if else match reg clock reset async_reset cross inst const enum struct interface Flipped mem readmemh inout tristate for fn type use assert assume cover",
             vec![
                 Ok((0, Token::Mod, 3)),
                 Ok((4, Token::Top, 7)),
                 Ok((8, Token::In, 10)),
                 Ok((11, Token::Out, 14)),
                 Ok((15, Token::Bit, 18)),
-                Ok((19, Token::Let, 22)),
+                Ok((19, Token::Bits, 23)),
+                Ok((24, Token::UInt, 28)),
+                Ok((29, Token::SInt, 33)),
+                Ok((34, Token::Let, 37)),
+                Ok((38, Token::If, 40)),
+                Ok((41, Token::Else, 45)),
+                Ok((46, Token::Match, 51)),
+                Ok((52, Token::Reg, 55)),
+                Ok((56, Token::Clock, 61)),
+                Ok((62, Token::Reset, 67)),
+                Ok((68, Token::AsyncReset, 79)),
+                Ok((80, Token::Cross, 85)),
+                Ok((86, Token::Inst, 90)),
+                Ok((91, Token::Const, 96)),
+                Ok((97, Token::Enum, 101)),
+                Ok((102, Token::Struct, 108)),
+                Ok((109, Token::Interface, 118)),
+                Ok((119, Token::Flipped, 126)),
+                Ok((127, Token::Mem, 130)),
+                Ok((131, Token::ReadMemH, 139)),
+                Ok((140, Token::InOut, 145)),
+                Ok((146, Token::Tristate, 154)),
+                Ok((155, Token::For, 158)),
+                Ok((159, Token::Fn, 161)),
+                Ok((162, Token::Type, 166)),
+                Ok((167, Token::Use, 170)),
+                Ok((171, Token::Assert, 177)),
+                Ok((178, Token::Assume, 184)),
+                Ok((185, Token::Cover, 190)),
             ],
         );
     }
@@ -273,7 +611,7 @@ mod test {
     #[test]
     fn operators() {
         assert_lex(
-            "= ~ & | ^ << >>",
+            "= ~ & | ^ << >> + - * == != <= >= && || ! / %",
             vec![
                 Ok((0, Token::Assign, 1)),
                 Ok((2, Token::Negate, 3)),
@@ -282,6 +620,18 @@ mod test {
                 Ok((8, Token::BitXor, 9)),
                 Ok((10, Token::ShiftLeft, 12)),
                 Ok((13, Token::ShiftRight, 15)),
+                Ok((16, Token::Plus, 17)),
+                Ok((18, Token::Minus, 19)),
+                Ok((20, Token::Star, 21)),
+                Ok((22, Token::Equal, 24)),
+                Ok((25, Token::NotEqual, 27)),
+                Ok((28, Token::LessEqual, 30)),
+                Ok((31, Token::GreaterEqual, 33)),
+                Ok((34, Token::LogicAnd, 36)),
+                Ok((37, Token::LogicOr, 39)),
+                Ok((40, Token::LogicNot, 41)),
+                Ok((42, Token::Slash, 43)),
+                Ok((44, Token::Percent, 45)),
             ],
         );
     }
@@ -289,7 +639,7 @@ mod test {
     #[test]
     fn punctuation() {
         assert_lex(
-            ", ; : ( ) { }",
+            ", ; : ( ) { } < > [ ] => _ :: # . .. -> @",
             vec![
                 Ok((0, Token::Comma, 1)),
                 Ok((2, Token::Semicolon, 3)),
@@ -298,6 +648,18 @@ mod test {
                 Ok((8, Token::RightParen, 9)),
                 Ok((10, Token::LeftCurlyBrace, 11)),
                 Ok((12, Token::RightCurlyBrace, 13)),
+                Ok((14, Token::LeftAngleBracket, 15)),
+                Ok((16, Token::RightAngleBracket, 17)),
+                Ok((18, Token::LeftSquareBracket, 19)),
+                Ok((20, Token::RightSquareBracket, 21)),
+                Ok((22, Token::FatArrow, 24)),
+                Ok((25, Token::Underscore, 26)),
+                Ok((27, Token::PathSep, 29)),
+                Ok((30, Token::Hash, 31)),
+                Ok((32, Token::Dot, 33)),
+                Ok((34, Token::DotDot, 36)),
+                Ok((37, Token::Arrow, 39)),
+                Ok((40, Token::At, 41)),
             ],
         );
     }
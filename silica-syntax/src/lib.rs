@@ -1,11 +1,20 @@
+use std::path::PathBuf;
+
 use lalrpop_util::lalrpop_mod;
 
 pub mod ast;
+pub mod check;
+pub mod elab;
+pub mod eval;
+pub mod source;
 pub mod visit;
 
 mod error;
 mod lexer;
-lalrpop_mod!(parser);
+lalrpop_mod!(
+    #[allow(clippy::all, unused_parens)]
+    parser
+);
 
 /// Source code byte offsets, used for spans and errors.
 pub type BytePos = usize;
@@ -27,11 +36,51 @@ pub enum Error {
         pos: BytePos,
         ch: char,
     },
+    UnreadableFile {
+        path: PathBuf,
+    },
+    /// No file along the search path matches the file named by a `use`.
+    UnresolvedUse {
+        path: String,
+        pos: BytePos,
+    },
+    /// The file named by a `use` doesn't declare the item it brings in.
+    UndeclaredItem {
+        name: String,
+        pos: BytePos,
+    },
+}
+
+impl Error {
+    /// The position the error was found at, if it has one.
+    pub fn pos(&self) -> Option<BytePos> {
+        match self {
+            Error::InvalidToken { pos }
+            | Error::InvalidCharacter { pos, .. }
+            | Error::UnresolvedUse { pos, .. }
+            | Error::UndeclaredItem { pos, .. } => Some(*pos),
+            Error::UnrecognizedToken { token, .. } => token.as_ref().map(|(pos, _, _)| *pos),
+            Error::ExtraToken { token: (pos, _, _) } => Some(*pos),
+            Error::UnreadableFile { .. } => None,
+        }
+    }
 }
 
 /// Parses source code into an AST.
 pub fn parse_source(source: &str) -> Result<ast::Ast, Error> {
-    let lexer = lexer::Lexer::new(source);
+    parse_file(source, 0)
+}
+
+/// Parses the source code of a file whose first byte is at `start` within a
+/// source map, so that positions point into that file.
+fn parse_file(source: &str, start: BytePos) -> Result<ast::Ast, Error> {
+    let lexer = lexer::Lexer::new(source).map(|item| match item {
+        Ok((l, token, r)) => Ok((start + l, token, start + r)),
+        Err(lexer::InvalidCharacterError { pos, ch }) => Err(lexer::InvalidCharacterError {
+            pos: start + pos,
+            ch,
+        }),
+    });
     let ast = parser::SourceFileParser::new().parse(lexer)?;
 
     Ok(ast)
@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Ast, Stmt, Use};
+use crate::visit::{walk_stmt, Visitor};
+use crate::{parse_file, BytePos, Error};
+
+/// Identifies a file within a [`SourceMap`].
+pub type FileId = usize;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub source: String,
+    /// The position of the first byte of the file within the source map.
+    pub start: BytePos,
+}
+
+impl SourceFile {
+    fn end(&self) -> BytePos {
+        self.start + self.source.len()
+    }
+}
+
+/// A position within a file, with lines and columns counted from one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub file: FileId,
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Every file loaded into a design. Each file takes up its own range of
+/// positions, so a position found in an error leads back to a single file.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    /// Adds a file, returning its ID. Files are kept one position apart, so
+    /// that the end of a file isn't mistaken for the start of the next.
+    pub fn add(&mut self, path: PathBuf, source: String) -> FileId {
+        let start = self.files.last().map_or(0, |f| f.end() + 1);
+        self.files.push(SourceFile {
+            path,
+            source,
+            start,
+        });
+        self.files.len() - 1
+    }
+
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id]
+    }
+
+    /// Looks up a file by path.
+    pub fn find(&self, path: &Path) -> Option<FileId> {
+        self.files.iter().position(|f| f.path == path)
+    }
+
+    /// The file, line and column a position falls on.
+    pub fn location(&self, pos: BytePos) -> Option<Location> {
+        let file = self
+            .files
+            .iter()
+            .position(|f| f.start <= pos && pos <= f.end())?;
+        let before = self.files[file]
+            .source
+            .get(..pos - self.files[file].start)?;
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+
+        Some(Location {
+            file,
+            line: before.matches('\n').count() + 1,
+            col: before[line_start..].chars().count() + 1,
+        })
+    }
+}
+
+/// Loads a file along with every file it uses, directly or not, and combines
+/// their items. The file named by a `use`, or by an instance of a module
+/// written as a path, is looked for within each of the `search` directories
+/// in turn. Items from every file share one namespace,
+/// like the modules they become once lowered.
+pub fn load(path: &Path, search: &[PathBuf], map: &mut SourceMap) -> Result<Ast, Error> {
+    let mut asts = vec![load_file(path, map)?];
+    // Files are loaded in the order they are first used in.
+    let mut pending: VecDeque<Use> = used(&asts[0]).into();
+    while let Some(u) = pending.pop_front() {
+        let path = resolve_use(&u, search)?;
+        let id = match map.find(&path) {
+            Some(id) => id,
+            None => {
+                let ast = load_file(&path, map)?;
+                pending.extend(used(&ast));
+                asts.push(ast);
+                asts.len() - 1
+            }
+        };
+        if !asts[id].declares(&u.item) {
+            return Err(Error::UndeclaredItem {
+                name: u.item.clone(),
+                pos: u.pos,
+            });
+        }
+    }
+
+    let mut merged = Ast::default();
+    for ast in asts {
+        merged.merge(ast);
+    }
+
+    Ok(merged)
+}
+
+/// The items a file uses from others: those of its `use` declarations, along
+/// with the modules it instantiates by path.
+fn used(ast: &Ast) -> Vec<Use> {
+    struct Paths(Vec<Use>);
+
+    impl Visitor for Paths {
+        fn visit_stmt(&mut self, s: &Stmt) {
+            if let Stmt::Inst {
+                file, module, pos, ..
+            } = s
+            {
+                if !file.is_empty() {
+                    self.0.push(Use {
+                        file: file.clone(),
+                        item: module.clone(),
+                        pos: *pos,
+                    });
+                }
+            }
+            walk_stmt(self, s);
+        }
+    }
+
+    let mut paths = Paths(ast.uses.clone());
+    for m in &ast.mods {
+        paths.visit_mod(m);
+    }
+    paths.0
+}
+
+/// Reads and parses a file, adding it to the source map. Paths are made
+/// canonical so that a file is only ever loaded once.
+fn load_file(path: &Path, map: &mut SourceMap) -> Result<Ast, Error> {
+    let unreadable = || Error::UnreadableFile {
+        path: path.to_path_buf(),
+    };
+    let path = fs::canonicalize(path).map_err(|_| unreadable())?;
+    let source = fs::read_to_string(&path).map_err(|_| unreadable())?;
+    let id = map.add(path, source);
+    let file = map.file(id);
+
+    parse_file(&file.source, file.start)
+}
+
+/// The first file along the search path named by a `use`.
+fn resolve_use(u: &Use, search: &[PathBuf]) -> Result<PathBuf, Error> {
+    let relative: PathBuf = u.file.iter().collect();
+    search
+        .iter()
+        .map(|dir| dir.join(&relative).with_extension("si"))
+        .find(|path| path.is_file())
+        .and_then(|path| fs::canonicalize(path).ok())
+        .ok_or_else(|| Error::UnresolvedUse {
+            path: u.file.join("::"),
+            pos: u.pos,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let mut map = SourceMap::new();
+        let a = map.add(PathBuf::from("a.si"), "ab\ncd".to_string());
+        let b = map.add(PathBuf::from("b.si"), "\nef".to_string());
+
+        assert_eq!(map.file(b).start, 6);
+        assert_eq!(
+            map.location(4),
+            Some(Location {
+                file: a,
+                line: 2,
+                col: 2
+            })
+        );
+        assert_eq!(
+            map.location(8),
+            Some(Location {
+                file: b,
+                line: 2,
+                col: 2
+            })
+        );
+        assert_eq!(map.location(10), None);
+    }
+}
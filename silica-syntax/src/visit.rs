@@ -6,6 +6,15 @@ pub trait Visitor: Sized {
     fn visit_mod(&mut self, m: &Mod) {
         walk_mod(self, m);
     }
+    fn visit_const(&mut self, c: &Const) {
+        walk_const(self, c);
+    }
+    fn visit_attr(&mut self, a: &Attr) {
+        walk_attr(self, a);
+    }
+    fn visit_param(&mut self, p: &Param) {
+        walk_param(self, p);
+    }
     fn visit_port(&mut self, p: &Port) {
         walk_port(self, p);
     }
@@ -18,9 +27,18 @@ pub trait Visitor: Sized {
     fn visit_stmt(&mut self, s: &Stmt) {
         walk_stmt(self, s);
     }
+    fn visit_conn(&mut self, c: &Conn) {
+        walk_conn(self, c);
+    }
     fn visit_expr(&mut self, e: &Expr) {
         walk_expr(self, e);
     }
+    fn visit_arm(&mut self, a: &Arm) {
+        walk_arm(self, a);
+    }
+    fn visit_pat(&mut self, p: &Pat) {
+        walk_pat(self, p);
+    }
     fn visit_unary_op(&mut self, u: &UnaryOp) {
         walk_unary_op(self, u);
     }
@@ -30,8 +48,22 @@ pub trait Visitor: Sized {
 }
 
 pub fn walk_mod<V: Visitor>(visitor: &mut V, m: &Mod) {
-    let Mod { name, ports, stmts } = m;
+    let Mod {
+        name,
+        attrs,
+        top,
+        params,
+        ports,
+        stmts,
+        pos,
+    } = m;
 
+    for attr in attrs {
+        visitor.visit_attr(attr);
+    }
+    for param in params {
+        visitor.visit_param(param);
+    }
     for port in ports {
         visitor.visit_port(port);
     }
@@ -40,50 +72,270 @@ pub fn walk_mod<V: Visitor>(visitor: &mut V, m: &Mod) {
     }
 }
 
+pub fn walk_const<V: Visitor>(visitor: &mut V, c: &Const) {
+    let Const { name, ty, ex, pos } = c;
+
+    if let Some(ty) = ty {
+        visitor.visit_type(ty);
+    }
+    visitor.visit_expr(ex);
+}
+
+pub fn walk_attr<V: Visitor>(visitor: &mut V, a: &Attr) {}
+
+pub fn walk_param<V: Visitor>(visitor: &mut V, p: &Param) {
+    let Param { name, default, pos } = p;
+
+    visitor.visit_expr(default);
+}
+
 pub fn walk_port<V: Visitor>(visitor: &mut V, p: &Port) {
-    let Port { dir, name, ty } = p;
+    let Port {
+        dir,
+        name,
+        ty,
+        attrs,
+        pos,
+    } = p;
 
+    for attr in attrs {
+        visitor.visit_attr(attr);
+    }
     visitor.visit_dir(dir);
     visitor.visit_type(ty);
 }
 
 pub fn walk_dir<V: Visitor>(visitor: &mut V, d: &Dir) {}
 
-pub fn walk_type<V: Visitor>(visitor: &mut V, t: &Type) {}
+pub fn walk_type<V: Visitor>(visitor: &mut V, t: &Type) {
+    match t {
+        Type::Generic { kind, width, pos } => {
+            visitor.visit_expr(width);
+        }
+        Type::Array { elem, len } => {
+            visitor.visit_type(elem);
+        }
+        Type::GenericArray { elem, len, pos } => {
+            visitor.visit_type(elem);
+            visitor.visit_expr(len);
+        }
+        Type::Alias { name, ty } => {
+            visitor.visit_type(ty);
+        }
+        _ => {}
+    }
+}
 
 pub fn walk_stmt<V: Visitor>(visitor: &mut V, s: &Stmt) {
     match s {
-        Stmt::Assign { id, ex } => {
+        Stmt::Const(c) => {
+            visitor.visit_const(c);
+        }
+        Stmt::Assign {
+            id,
+            fields,
+            ex,
+            pos,
+        } => {
             visitor.visit_expr(ex);
         }
-        Stmt::Declare { id, ty } => {
+        Stmt::Declare { id, ty, attrs, pos } => {
+            for attr in attrs {
+                visitor.visit_attr(attr);
+            }
+            visitor.visit_type(ty);
+        }
+        Stmt::DeclareAssign {
+            id,
+            ty,
+            ex,
+            attrs,
+            pos,
+        } => {
+            for attr in attrs {
+                visitor.visit_attr(attr);
+            }
             visitor.visit_type(ty);
+            visitor.visit_expr(ex);
         }
-        Stmt::DeclareAssign { id, ty, ex } => {
+        Stmt::Reg {
+            id,
+            ty,
+            clk,
+            rst,
+            init,
+            attrs,
+            pos,
+        } => {
+            for attr in attrs {
+                visitor.visit_attr(attr);
+            }
             visitor.visit_type(ty);
+            if let Some(init) = init {
+                visitor.visit_expr(init);
+            }
+        }
+        Stmt::Mem {
+            id,
+            ty,
+            clk,
+            init,
+            attrs,
+            pos,
+        } => {
+            for attr in attrs {
+                visitor.visit_attr(attr);
+            }
+            visitor.visit_type(ty);
+            if let Some(MemInit::Const(ex)) = init {
+                visitor.visit_expr(ex);
+            }
+        }
+        Stmt::MemWrite {
+            id,
+            addr,
+            ex,
+            en,
+            pos,
+        } => {
+            visitor.visit_expr(addr);
+            visitor.visit_expr(ex);
+            if let Some(en) = en {
+                visitor.visit_expr(en);
+            }
+        }
+        Stmt::For {
+            var,
+            start,
+            end,
+            body,
+            pos,
+        } => {
+            visitor.visit_expr(start);
+            visitor.visit_expr(end);
+            for stmt in body {
+                visitor.visit_stmt(stmt);
+            }
+        }
+        Stmt::If {
+            cond,
+            tstmts,
+            fstmts,
+            pos,
+        } => {
+            visitor.visit_expr(cond);
+            for stmt in tstmts.iter().chain(fstmts) {
+                visitor.visit_stmt(stmt);
+            }
+        }
+        Stmt::Inst {
+            id,
+            file,
+            module,
+            params,
+            conns,
+            pos,
+        } => {
+            for param in params {
+                visitor.visit_conn(param);
+            }
+            for conn in conns {
+                visitor.visit_conn(conn);
+            }
+        }
+        Stmt::Assert {
+            kind,
+            ex,
+            clk,
+            rst,
+            pos,
+        } => {
             visitor.visit_expr(ex);
         }
     }
 }
 
+pub fn walk_conn<V: Visitor>(visitor: &mut V, c: &Conn) {
+    let Conn { port, ex, pos } = c;
+
+    visitor.visit_expr(ex);
+}
+
 pub fn walk_expr<V: Visitor>(visitor: &mut V, e: &Expr) {
     match e {
-        Expr::Binary { lex, op, rex } => {
+        Expr::Binary { lex, op, rex, pos } => {
             visitor.visit_expr(lex);
             visitor.visit_binary_op(op);
             visitor.visit_expr(rex);
         }
         Expr::Unary { op, ex } => {
             visitor.visit_unary_op(op);
+            visitor.visit_expr(ex);
         }
         Expr::Paren { ex } => {
             visitor.visit_expr(ex);
         }
+        Expr::If { cond, tex, fex } => {
+            visitor.visit_expr(cond);
+            visitor.visit_expr(tex);
+            visitor.visit_expr(fex);
+        }
+        Expr::Match { ex, arms } => {
+            visitor.visit_expr(ex);
+            for arm in arms {
+                visitor.visit_arm(arm);
+            }
+        }
+        Expr::Index { ex, idx } => {
+            visitor.visit_expr(ex);
+            visitor.visit_expr(idx);
+        }
+        Expr::Slice { ex, msb, lsb } => {
+            visitor.visit_expr(ex);
+            visitor.visit_expr(msb);
+            visitor.visit_expr(lsb);
+        }
+        Expr::Concat { exs } | Expr::Array { exs } => {
+            for ex in exs {
+                visitor.visit_expr(ex);
+            }
+        }
+        Expr::Repeat { count, ex } => {
+            visitor.visit_expr(count);
+            visitor.visit_expr(ex);
+        }
+        Expr::Call { name, args } => {
+            for arg in args {
+                visitor.visit_expr(arg);
+            }
+        }
+        Expr::Tristate { en, ex } => {
+            visitor.visit_expr(en);
+            visitor.visit_expr(ex);
+        }
+        Expr::Cross { ex } => {
+            visitor.visit_expr(ex);
+        }
+        Expr::Field { ex, field } => {
+            visitor.visit_expr(ex);
+        }
+        Expr::Variant { ty, variant } => {}
         Expr::Ident { id } => {}
-        Expr::Litrl { val } => {}
+        Expr::Litrl { val, width } => {}
     }
 }
 
+pub fn walk_arm<V: Visitor>(visitor: &mut V, a: &Arm) {
+    let Arm { pats, ex } = a;
+
+    for pat in pats {
+        visitor.visit_pat(pat);
+    }
+    visitor.visit_expr(ex);
+}
+
+pub fn walk_pat<V: Visitor>(visitor: &mut V, p: &Pat) {}
+
 pub fn walk_unary_op<V: Visitor>(visitor: &mut V, u: &UnaryOp) {}
 
 pub fn walk_binary_op<V: Visitor>(visitor: &mut V, b: &BinaryOp) {}
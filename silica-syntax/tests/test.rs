@@ -1,11 +1,15 @@
-use syntax::{ast::*, parse_source};
+use num_bigint::{BigInt, BigUint};
+use syntax::{
+    ast::*, check::check_ast, check::warnings, check::Error, check::Scope, check::Warning,
+    parse_source,
+};
 
 fn assert_expr(source: &str, expected: &Expr) {
     let module = format!("top mod a ( out y: bit ) {{ y = {}; }}", source);
 
     let ast = parse_source(&module).unwrap();
-    let expr = match ast.top.stmts[0] {
-        Stmt::Assign { id: _, ref ex } => ex,
+    let expr = match ast.mods[0].stmts[0] {
+        Stmt::Assign { ref ex, .. } => ex,
         _ => panic!(),
     };
 
@@ -16,7 +20,7 @@ fn assert_stmt(source: &str, expected: &Stmt) {
     let module = format!("top mod a ( out y: bit ) {{ {} }}", source);
 
     let ast = parse_source(&module).unwrap();
-    let stmt = &ast.top.stmts[0];
+    let stmt = &ast.mods[0].stmts[0];
 
     assert_eq!(stmt, expected);
 }
@@ -27,9 +31,12 @@ fn assign_stmt() {
         "y = 5;",
         &Stmt::Assign {
             id: "y".to_string(),
+            fields: vec![],
             ex: Expr::Litrl {
-                val: "5".to_string(),
+                val: BigUint::from(5u32),
+                width: None,
             },
+            pos: 27,
         },
     );
 }
@@ -41,6 +48,8 @@ fn declare_stmt() {
         &Stmt::Declare {
             id: "y".to_string(),
             ty: Type::Bit,
+            attrs: vec![],
+            pos: 31,
         },
     );
 }
@@ -53,45 +62,2032 @@ fn declare_assign_stmt() {
             id: "y".to_string(),
             ty: Type::Bit,
             ex: Expr::Litrl {
-                val: "1".to_string(),
+                val: BigUint::from(1u32),
+                width: None,
             },
+            attrs: vec![],
+            pos: 31,
         },
     );
 }
 
+#[test]
+fn bits_type() {
+    assert_stmt(
+        "let y: bits<8>;",
+        &Stmt::Declare {
+            id: "y".to_string(),
+            ty: Type::Bits { width: 8 },
+            attrs: vec![],
+            pos: 31,
+        },
+    );
+}
+
+#[test]
+fn zero_width_bits_type() {
+    let source = "top mod a ( out y: bits<0> ) { }";
+
+    assert!(parse_source(source).is_err());
+}
+
+#[test]
+fn integer_types() {
+    assert_stmt(
+        "let y: uint<4>;",
+        &Stmt::Declare {
+            id: "y".to_string(),
+            ty: Type::UInt { width: 4 },
+            attrs: vec![],
+            pos: 31,
+        },
+    );
+    assert_stmt(
+        "let y: sint<16>;",
+        &Stmt::Declare {
+            id: "y".to_string(),
+            ty: Type::SInt { width: 16 },
+            attrs: vec![],
+            pos: 31,
+        },
+    );
+}
+
+#[test]
+fn mixed_signedness() {
+    let source = "top mod a ( in x: sint<8>, in y: uint<8>, out z: sint<8> ) {
+            z = x & y;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedSignedness {
+            lty: Type::SInt { width: 8 },
+            rty: Type::UInt { width: 8 },
+            pos: 73,
+        })
+    );
+}
+
+#[test]
+fn unsized_signed_operand() {
+    let source = "top mod a ( in x: sint<8>, out y: bit ) { y = x < 200; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::LiteralOutOfRange {
+            val: BigInt::from(200),
+            ty: Type::SInt { width: 8 },
+            pos: 42,
+        })
+    );
+}
+
+#[test]
+fn undeclared_identifier() {
+    let source = "top mod a ( out y: bit ) { y = x; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UndeclaredIdentifier {
+            id: "x".to_string(),
+            pos: 27
+        })
+    );
+}
+
+#[test]
+fn literals() {
+    assert_expr(
+        "0xFF",
+        &Expr::Litrl {
+            val: BigUint::from(255u32),
+            width: None,
+        },
+    );
+    assert_expr(
+        "0b1010_1010w12",
+        &Expr::Litrl {
+            val: BigUint::from(170u32),
+            width: Some(12),
+        },
+    );
+}
+
+#[test]
+fn overflowing_literal() {
+    let source = "top mod a ( out y: bits<8> ) { y = 0x100w8; }";
+
+    assert!(parse_source(source).is_err());
+}
+
+#[test]
+fn index_and_slice() {
+    assert_expr(
+        "a[3] | b[7:4]",
+        &Expr::Binary {
+            lex: Box::new(Expr::Index {
+                ex: Box::new(Expr::Ident {
+                    id: "a".to_string(),
+                }),
+                idx: Box::new(Expr::Litrl {
+                    val: BigUint::from(3u32),
+                    width: None,
+                }),
+            }),
+            op: BinaryOp::BitOr,
+            rex: Box::new(Expr::Slice {
+                ex: Box::new(Expr::Ident {
+                    id: "b".to_string(),
+                }),
+                msb: Box::new(Expr::Litrl {
+                    val: BigUint::from(7u32),
+                    width: None,
+                }),
+                lsb: Box::new(Expr::Litrl {
+                    val: BigUint::from(4u32),
+                    width: None,
+                }),
+            }),
+            pos: 36,
+        },
+    );
+}
+
+#[test]
+fn index_out_of_bounds() {
+    let source = "top mod a ( in x: bits<8>, out y: bits<4> ) { y = x[8:5]; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::IndexOutOfBounds {
+            idx: 8,
+            width: 8,
+            pos: 46
+        })
+    );
+}
+
+#[test]
+fn reversed_slice() {
+    let source = "top mod a ( in x: bits<8>, out y: bits<4> ) { y = x[0:3]; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ReversedSlice {
+            msb: 0,
+            lsb: 3,
+            pos: 46
+        })
+    );
+}
+
+#[test]
+fn concat_and_repeat() {
+    assert_expr(
+        "{ {2{a}}, b }",
+        &Expr::Concat {
+            exs: vec![
+                Expr::Repeat {
+                    count: Box::new(Expr::Litrl {
+                        val: BigUint::from(2u32),
+                        width: None,
+                    }),
+                    ex: Box::new(Expr::Concat {
+                        exs: vec![Expr::Ident {
+                            id: "a".to_string(),
+                        }],
+                    }),
+                },
+                Expr::Ident {
+                    id: "b".to_string(),
+                },
+            ],
+        },
+    );
+}
+
+#[test]
+fn unsized_concat_operand() {
+    let source = "top mod a ( in x: bits<4>, out y: bits<8> ) { y = {x, 0}; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UnsizedConcatOperand { pos: 46 })
+    );
+}
+
+#[test]
+fn arithmetic_precedence() {
+    assert_expr(
+        "a + b * c - d",
+        &Expr::Binary {
+            lex: Box::new(Expr::Binary {
+                lex: Box::new(Expr::Ident {
+                    id: "a".to_string(),
+                }),
+                op: BinaryOp::Add,
+                rex: Box::new(Expr::Binary {
+                    lex: Box::new(Expr::Ident {
+                        id: "b".to_string(),
+                    }),
+                    op: BinaryOp::Mul,
+                    rex: Box::new(Expr::Ident {
+                        id: "c".to_string(),
+                    }),
+                    pos: 37,
+                }),
+                pos: 33,
+            }),
+            op: BinaryOp::Sub,
+            rex: Box::new(Expr::Ident {
+                id: "d".to_string(),
+            }),
+            pos: 41,
+        },
+    );
+}
+
+#[test]
+fn arithmetic_widths() {
+    let source = "top mod a ( in x: uint<8>, in y: uint<4>, out z: uint<12> ) {
+            z = x + y;
+            z = x - 1;
+            z = x * y;
+        }";
+
+    let ast = parse_source(source).unwrap();
+    let scope = Scope::new(&ast, &ast.mods[0]).unwrap();
+    let widths: Vec<Type> = ast.mods[0]
+        .stmts
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Assign { ex, .. } => scope.type_of(ex).unwrap(),
+            _ => panic!(),
+        })
+        .collect();
+
+    assert_eq!(
+        widths,
+        vec![
+            Type::UInt { width: 9 },
+            Type::UInt { width: 9 },
+            Type::UInt { width: 12 },
+        ]
+    );
+}
+
+#[test]
+fn compare_precedence() {
+    assert_expr(
+        "!a || b & c == d && e",
+        &Expr::Binary {
+            lex: Box::new(Expr::Unary {
+                op: UnaryOp::LogicNot,
+                ex: Box::new(Expr::Ident {
+                    id: "a".to_string(),
+                }),
+            }),
+            op: BinaryOp::LogicOr,
+            rex: Box::new(Expr::Binary {
+                lex: Box::new(Expr::Binary {
+                    lex: Box::new(Expr::Binary {
+                        lex: Box::new(Expr::Ident {
+                            id: "b".to_string(),
+                        }),
+                        op: BinaryOp::BitAnd,
+                        rex: Box::new(Expr::Ident {
+                            id: "c".to_string(),
+                        }),
+                        pos: 39,
+                    }),
+                    op: BinaryOp::Equal,
+                    rex: Box::new(Expr::Ident {
+                        id: "d".to_string(),
+                    }),
+                    pos: 43,
+                }),
+                op: BinaryOp::LogicAnd,
+                rex: Box::new(Expr::Ident {
+                    id: "e".to_string(),
+                }),
+                pos: 48,
+            }),
+            pos: 34,
+        },
+    );
+}
+
+#[test]
+fn chained_comparison() {
+    let source = "top mod a ( out y: bit ) { y = 1 < 2 < 3; }";
+
+    assert!(parse_source(source).is_err());
+}
+
+#[test]
+fn logic_operand_width() {
+    let source = "top mod a ( in x: bits<4>, out y: bit ) { y = !x; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedBit {
+            ty: Type::Bits { width: 4 },
+            pos: 42
+        })
+    );
+}
+
+#[test]
+fn if_expr() {
+    assert_expr(
+        "if a { b } else if c { d } else { e }",
+        &Expr::If {
+            cond: Box::new(Expr::Ident {
+                id: "a".to_string(),
+            }),
+            tex: Box::new(Expr::Ident {
+                id: "b".to_string(),
+            }),
+            fex: Box::new(Expr::If {
+                cond: Box::new(Expr::Ident {
+                    id: "c".to_string(),
+                }),
+                tex: Box::new(Expr::Ident {
+                    id: "d".to_string(),
+                }),
+                fex: Box::new(Expr::Ident {
+                    id: "e".to_string(),
+                }),
+            }),
+        },
+    );
+}
+
+#[test]
+fn if_expr_widths() {
+    let source = "top mod a ( in c: bit, in x: bits<4>, in z: bits<8>, out y: bits<8> ) {
+            y = if c { x } else { z };
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedWidth {
+            lty: Type::Bits { width: 4 },
+            rty: Type::Bits { width: 8 },
+            pos: 84,
+        })
+    );
+}
+
+#[test]
+fn if_expr_condition() {
+    let source = "top mod a ( in c: bits<2>, in x: bits<8>, out y: bits<8> ) {
+            y = if c { x } else { 0 };
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedBit {
+            ty: Type::Bits { width: 2 },
+            pos: 73
+        })
+    );
+}
+
+#[test]
+fn match_expr() {
+    assert_expr(
+        "match a { 0 | 1 => b, _ => c }",
+        &Expr::Match {
+            ex: Box::new(Expr::Ident {
+                id: "a".to_string(),
+            }),
+            arms: vec![
+                Arm {
+                    pats: vec![
+                        Pat::Litrl {
+                            val: BigUint::from(0u32),
+                            width: None,
+                        },
+                        Pat::Litrl {
+                            val: BigUint::from(1u32),
+                            width: None,
+                        },
+                    ],
+                    ex: Expr::Ident {
+                        id: "b".to_string(),
+                    },
+                },
+                Arm {
+                    pats: vec![Pat::Wildcard],
+                    ex: Expr::Ident {
+                        id: "c".to_string(),
+                    },
+                },
+            ],
+        },
+    );
+}
+
+#[test]
+fn non_exhaustive_match() {
+    let source = "top mod a ( in x: bits<2>, out y: bits<4> ) {
+            y = match x { 0 => 1, 1 => 2, 2 => 4 };
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::NonExhaustiveMatch { pos: 58 }));
+}
+
+#[test]
+fn exhaustive_match() {
+    let source = "top mod a ( in x: bits<2>, out y: bits<4> ) {
+            y = match x { 0 => 1, 1 => 2, 2 => 4, 3 => 8 };
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn match_pattern_range() {
+    let source = "top mod a ( in x: bits<2>, out y: bits<4> ) {
+            y = match x { 4 => 1, _ => 0 };
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::PatternOutOfRange {
+            val: BigUint::from(4u32),
+            width: 2,
+            pos: 58
+        })
+    );
+}
+
+#[test]
+fn reg_stmt() {
+    assert_stmt(
+        "reg(clk) q: bits<4>;",
+        &Stmt::Reg {
+            id: "q".to_string(),
+            ty: Type::Bits { width: 4 },
+            clk: "clk".to_string(),
+            rst: None,
+            init: None,
+            attrs: vec![],
+            pos: 36,
+        },
+    );
+    assert_stmt(
+        "reg(clk, rst) q: bits<4> = 0;",
+        &Stmt::Reg {
+            id: "q".to_string(),
+            ty: Type::Bits { width: 4 },
+            clk: "clk".to_string(),
+            rst: Some("rst".to_string()),
+            init: Some(Expr::Litrl {
+                val: BigUint::from(0u32),
+                width: None,
+            }),
+            attrs: vec![],
+            pos: 41,
+        },
+    );
+}
+
+#[test]
+fn missing_reset_value() {
+    let source = "top mod a ( in clk: clock, in rst: reset, out y: bit ) {
+            reg(clk, rst) q: bit;
+            y = q;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MissingResetValue {
+            id: "q".to_string(),
+            pos: 83
+        })
+    );
+}
+
+#[test]
+fn clock_and_reset_types() {
+    assert_stmt(
+        "let c: clock;",
+        &Stmt::Declare {
+            id: "c".to_string(),
+            ty: Type::Clock,
+            attrs: vec![],
+            pos: 31,
+        },
+    );
+    assert_stmt(
+        "let r: async_reset;",
+        &Stmt::Declare {
+            id: "r".to_string(),
+            ty: Type::AsyncReset,
+            attrs: vec![],
+            pos: 31,
+        },
+    );
+}
+
+#[test]
+fn data_as_clock() {
+    let source = "top mod a ( in clk: bit, in d: bit, out y: bit ) {
+            reg(clk) q: bit;
+            q = d;
+            y = q;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedClock {
+            ty: Type::Bit,
+            pos: 72
+        })
+    );
+}
+
+#[test]
+fn clock_as_data() {
+    let source = "top mod a ( in clk: clock, out y: bit ) { y = ~clk; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedData {
+            id: "clk".to_string(),
+            ty: Type::Clock,
+            pos: 42,
+        })
+    );
+}
+
+#[test]
+fn clock_domain_crossing() {
+    let source = "top mod a ( in clk_a: clock, in clk_b: clock, in d: bit, out y: bit ) {
+            reg(clk_a) a: bit;
+            a = d;
+            reg(clk_b) b: bit;
+            b = a;
+            reg(clk_b) c: bit;
+            c = cross(a);
+            y = b ^ c;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ClockDomainCrossing {
+            id: "b".to_string(),
+            from: "clk_a".to_string(),
+            to: "clk_b".to_string(),
+            pos: 165,
+        })
+    );
+}
+
+#[test]
+fn multiple_modules() {
+    let source = "mod inv ( in a: bit, out y: bit ) { y = ~a; }
+        top mod a ( in x: bit, out y: bit ) {
+            inst i: inv ( a: x, y: y );
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(ast.mods.len(), 2);
+    assert_eq!(ast.top().map(|m| m.name.as_str()), Some("a"));
+    assert_eq!(
+        ast.mods[1].stmts[0],
+        Stmt::Inst {
+            id: "i".to_string(),
+            file: vec![],
+            module: "inv".to_string(),
+            params: vec![],
+            conns: vec![
+                Conn {
+                    port: "a".to_string(),
+                    ex: Expr::Ident {
+                        id: "x".to_string()
+                    },
+                    pos: 121,
+                },
+                Conn {
+                    port: "y".to_string(),
+                    ex: Expr::Ident {
+                        id: "y".to_string()
+                    },
+                    pos: 127,
+                },
+            ],
+            pos: 109,
+        }
+    );
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn inst_path() {
+    assert_stmt(
+        "inst f: lib::fifo::Fifo ( d: x );",
+        &Stmt::Inst {
+            id: "f".to_string(),
+            file: vec!["lib".to_string(), "fifo".to_string()],
+            module: "Fifo".to_string(),
+            params: vec![],
+            conns: vec![Conn {
+                port: "d".to_string(),
+                ex: Expr::Ident {
+                    id: "x".to_string(),
+                },
+                pos: 56,
+            }],
+            pos: 32,
+        },
+    );
+}
+
+#[test]
+fn missing_top() {
+    let source = "mod a ( out y: bit ) { y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::MissingTop));
+}
+
+#[test]
+fn unknown_port() {
+    let source = "mod inv ( in a: bit, out y: bit ) { y = ~a; }
+        top mod a ( in x: bit, out y: bit ) {
+            inst i: inv ( a: x, b: x, y: y );
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UnknownPort {
+            id: "i".to_string(),
+            port: "b".to_string(),
+            pos: 127,
+        })
+    );
+}
+
+#[test]
+fn recursive_instance() {
+    let source = "top mod a ( in x: bit, out y: bit ) {
+            inst i: a ( x: x, y: y );
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::RecursiveInstance {
+            name: "a".to_string(),
+            pos: 55
+        })
+    );
+}
+
+#[test]
+fn parameterized_module() {
+    let source = "top mod a<N: uint = 4>( in x: bits<N * 2>, out y: bits<N * 2> ) { y = x; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.mods[0].params,
+        vec![Param {
+            name: "N".to_string(),
+            default: Expr::Litrl {
+                val: BigUint::from(4u32),
+                width: None,
+            },
+            pos: 20,
+        }]
+    );
+    assert_eq!(
+        ast.mods[0].ports[0].ty,
+        Type::Generic {
+            kind: Vector::Bits,
+            width: Box::new(Expr::Binary {
+                lex: Box::new(Expr::Ident {
+                    id: "N".to_string()
+                }),
+                op: BinaryOp::Mul,
+                rex: Box::new(Expr::Litrl {
+                    val: BigUint::from(2u32),
+                    width: None,
+                }),
+                pos: 37,
+            }),
+            pos: 35,
+        }
+    );
+    assert_eq!(check_ast(&ast), Ok(()));
+
+    let resolved = syntax::elab::resolve_defaults(&ast, &ast.mods[0]).unwrap();
+    assert_eq!(resolved.ports[0].ty, Type::Bits { width: 8 });
+}
+
+#[test]
+fn duplicate_module() {
+    let source = "mod b ( in x: bit, out y: bit ) { y = x; }
+        mod b ( in x: bit, out y: bit ) { y = ~x; }
+        top mod a ( in x: bit, out y: bit ) { inst i: b ( x: x, y: y ); }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::DuplicateModule {
+            name: "b".to_string(),
+            pos: 55,
+            prev: 4
+        })
+    );
+}
+
+#[test]
+fn variant_name_clash() {
+    let source = "mod adder<W: uint = 4>( in a: uint<W>, out y: uint<W> ) { y = a; }
+        mod adder_W_8( in a: uint<8>, out y: uint<8> ) { y = a; }
+        top mod a ( in x: uint<8>, out y: uint<8> ) {
+            inst add: adder<W = 8> ( a: x, y: y );
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::DuplicateModule {
+            name: "adder_W_8".to_string(),
+            pos: 79,
+            prev: 4
+        })
+    );
+}
+
+#[test]
+fn zero_width_param() {
+    let source = "mod a<N: uint = 4>( in x: bits<N>, out y: bits<N> ) { y = x; }
+        top mod b ( in x: bit, out y: bit ) {
+            let z: bits<1>;
+            inst i: a<N = 0> ( x: x, y: z );
+            y = z;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::InvalidWidth {
+            pos: 31,
+            width: BigInt::from(0)
+        })
+    );
+}
+
+#[test]
+fn const_declarations() {
+    let source = "const W: uint = 8 / 2;
+        top mod a ( in x: bits<W>, out y: bits<W * 2> ) {
+            const MASK: bits<W> = (1 << W) - 1;
+            y = {x & MASK, x[W - 1:0]};
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.consts,
+        vec![Const {
+            name: "W".to_string(),
+            ty: None,
+            ex: Expr::Binary {
+                lex: Box::new(Expr::Litrl {
+                    val: BigUint::from(8u32),
+                    width: None,
+                }),
+                op: BinaryOp::Div,
+                rex: Box::new(Expr::Litrl {
+                    val: BigUint::from(2u32),
+                    width: None,
+                }),
+                pos: 18,
+            },
+            pos: 16,
+        }]
+    );
+    assert_eq!(check_ast(&ast), Ok(()));
+
+    let resolved = syntax::elab::resolve_defaults(&ast, &ast.mods[0]).unwrap();
+    let scope = Scope::new(&ast, &resolved).unwrap();
+    assert_eq!(resolved.ports[1].ty, Type::Bits { width: 8 });
+    assert_eq!(
+        scope.constant("MASK").map(|v| v.val.clone()),
+        Some(BigInt::from(15))
+    );
+}
+
+#[test]
+fn const_overflow() {
+    let source = "const W: uint = 4;
+top mod a ( out y: bits<W> ) {
+            const X: bits<W> = (1 << W) + 1;
+            y = X;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::Overflow {
+            pos: 81,
+            val: BigInt::from(17)
+        })
+    );
+}
+
+#[test]
+fn const_division_by_zero() {
+    let source = "top mod a ( out y: bits<4> ) {
+            const X: uint = 3 % (2 - 2);
+            y = X;
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::DivisionByZero { pos: 61 }));
+}
+
+#[test]
+fn const_repeat_overflow() {
+    let source = "top mod a ( out y: bits<4> ) {
+            const X: uint = {1000000000{0b1w1}};
+            y = X[3:0];
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::Overflow {
+            pos: 59,
+            val: BigInt::from(1000000000)
+        })
+    );
+}
+
+#[test]
+fn non_constant_division() {
+    let source = "top mod a ( in x: uint<8>, out y: uint<8> ) { y = x / 2; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::NonConstantDivision { pos: 46 }));
+}
+
+#[test]
+fn enum_declarations() {
+    let source = "#[encoding(gray)]
+enum Phase { A, B, C }
+        enum State { Idle, Busy }
+        top mod a ( in s: State, out y: bit ) { y = s == State::Busy; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.enums[0],
+        Enum {
+            name: "Phase".to_string(),
+            attrs: vec![Attr {
+                name: "encoding".to_string(),
+                value: Some("gray".to_string()),
+                pos: 2,
+            }],
+            variants: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        }
+    );
+    assert_eq!(
+        ast.mods[0].ports[0].ty,
+        Type::Named {
+            name: "State".to_string()
+        }
+    );
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn enum_encodings() {
+    let source = "enum Binary { A, B, C, D, E }
+        #[encoding(one_hot)]
+        enum OneHot { A, B, C, D, E }
+        #[encoding(gray)]
+        enum Gray { A, B, C, D, E }";
+
+    let ast = parse_source(source).unwrap();
+    let values =
+        |e: &Enum| -> Vec<BigUint> { e.variants.iter().map(|v| e.value(v).unwrap()).collect() };
+
+    assert_eq!(ast.enums[0].width(), 3);
+    assert_eq!(
+        values(&ast.enums[0]),
+        [0u32, 1, 2, 3, 4]
+            .iter()
+            .map(|&v| BigUint::from(v))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(ast.enums[1].width(), 5);
+    assert_eq!(
+        values(&ast.enums[1]),
+        [1u32, 2, 4, 8, 16]
+            .iter()
+            .map(|&v| BigUint::from(v))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(ast.enums[2].width(), 3);
+    assert_eq!(
+        values(&ast.enums[2]),
+        [0u32, 1, 3, 2, 6]
+            .iter()
+            .map(|&v| BigUint::from(v))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn invalid_encoding() {
+    let source = "#[encoding(thermometer)]
+        enum State { Idle, Busy }
+        top mod a ( out y: bit ) { y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::InvalidEncoding { pos: 2 }));
+}
+
+#[test]
+fn mixed_enums() {
+    let source = "enum State { Idle, Busy }
+        enum Mode { Read, Write }
+        top mod a ( in s: State, out y: bit ) { y = s == Mode::Read; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedEnum {
+            lty: Type::Enum {
+                name: "State".to_string(),
+                width: 1,
+            },
+            rty: Type::Enum {
+                name: "Mode".to_string(),
+                width: 1,
+            },
+            pos: 108,
+        })
+    );
+}
+
+#[test]
+fn enum_arithmetic() {
+    let source = "enum State { Idle, Busy, Done }
+        top mod a ( in s: State, out y: State ) { y = s + 1; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedEnum {
+            lty: Type::Enum {
+                name: "State".to_string(),
+                width: 2,
+            },
+            rty: Type::UInt { width: 1 },
+            pos: 82,
+        })
+    );
+}
+
+#[test]
+fn non_exhaustive_enum_match() {
+    let source = "enum State { Idle, Busy, Done }
+        top mod a ( in s: State, out y: bit ) {
+            y = match s { State::Idle => 0, State::Busy => 1 };
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::NonExhaustiveMatch { pos: 92 }));
+}
+
+#[test]
+fn unknown_variant() {
+    let source = "enum State { Idle, Busy }
+        top mod a ( in s: State, out y: bit ) { y = s == State::Done; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UnknownVariant {
+            ty: "State".to_string(),
+            variant: "Done".to_string(),
+            pos: 74,
+        })
+    );
+}
+
+#[test]
+fn struct_declarations() {
+    let source = "struct Pair { lo: bits<4>, hi: Half }
+        struct Half { val: bits<4> }
+        top mod a ( in p: Pair, out y: bits<4> ) { y = p.hi.val; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.structs[0],
+        Struct {
+            name: "Pair".to_string(),
+            fields: vec![
+                Field {
+                    name: "lo".to_string(),
+                    ty: Type::Bits { width: 4 },
+                    pos: 14,
+                },
+                Field {
+                    name: "hi".to_string(),
+                    ty: Type::Named {
+                        name: "Half".to_string()
+                    },
+                    pos: 27,
+                },
+            ],
+        }
+    );
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn field_assign() {
+    assert_stmt(
+        "y.lo.val = x.hi;",
+        &Stmt::Assign {
+            id: "y".to_string(),
+            fields: vec!["lo".to_string(), "val".to_string()],
+            ex: Expr::Field {
+                ex: Box::new(Expr::Ident {
+                    id: "x".to_string(),
+                }),
+                field: "hi".to_string(),
+            },
+            pos: 27,
+        },
+    );
+}
+
+#[test]
+fn unknown_field() {
+    let source = "struct Pair { lo: bits<4>, hi: bits<4> }
+        top mod a ( in p: Pair, out y: bits<4> ) { y = p.mid; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UnknownField {
+            ty: Type::Struct {
+                name: "Pair".to_string(),
+                fields: vec![
+                    Field {
+                        name: "lo".to_string(),
+                        ty: Type::Bits { width: 4 },
+                        pos: 14,
+                    },
+                    Field {
+                        name: "hi".to_string(),
+                        ty: Type::Bits { width: 4 },
+                        pos: 27,
+                    },
+                ],
+            },
+            field: "mid".to_string(),
+            pos: 92,
+        })
+    );
+}
+
+#[test]
+fn recursive_struct() {
+    let source = "struct List { head: bit, tail: Node }
+        struct Node { next: List }
+        top mod a ( out y: bit ) { y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::RecursiveType {
+            name: "List".to_string()
+        })
+    );
+}
+
+#[test]
+fn mixed_structs() {
+    let source = "struct Pair { lo: bits<4>, hi: bits<4> }
+        top mod a ( in p: Pair, out y: bits<8> ) { y = p; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedStruct {
+            lty: Type::Bits { width: 8 },
+            rty: Type::Struct {
+                name: "Pair".to_string(),
+                fields: vec![
+                    Field {
+                        name: "lo".to_string(),
+                        ty: Type::Bits { width: 4 },
+                        pos: 14,
+                    },
+                    Field {
+                        name: "hi".to_string(),
+                        ty: Type::Bits { width: 4 },
+                        pos: 27,
+                    },
+                ],
+            },
+            pos: 92,
+        })
+    );
+}
+
+#[test]
+fn struct_operation() {
+    let source = "struct Pair { lo: bits<4>, hi: bits<4> }
+        top mod a ( in p: Pair, in q: Pair, out y: bit ) { y = p == q; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::StructOperation {
+            ty: Type::Struct {
+                name: "Pair".to_string(),
+                fields: vec![
+                    Field {
+                        name: "lo".to_string(),
+                        ty: Type::Bits { width: 4 },
+                        pos: 14,
+                    },
+                    Field {
+                        name: "hi".to_string(),
+                        ty: Type::Bits { width: 4 },
+                        pos: 27,
+                    },
+                ],
+            },
+            pos: 100,
+        })
+    );
+}
+
+#[test]
+fn interface_declarations() {
+    let source = "interface Stream { out valid: bit, in ready: bit }
+        top mod a ( out tx: Flipped<Stream>, out y: bit ) { tx.ready = 1; y = tx.valid; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.interfaces[0],
+        Interface {
+            name: "Stream".to_string(),
+            fields: vec![
+                Port {
+                    dir: Dir::Output,
+                    name: "valid".to_string(),
+                    ty: Type::Bit,
+                    attrs: vec![],
+                    pos: 23,
+                },
+                Port {
+                    dir: Dir::Input,
+                    name: "ready".to_string(),
+                    ty: Type::Bit,
+                    attrs: vec![],
+                    pos: 38,
+                },
+            ],
+        }
+    );
+    assert_eq!(
+        ast.mods[0].ports[0].ty,
+        Type::Flipped {
+            ty: Box::new(Type::Named {
+                name: "Stream".to_string()
+            })
+        }
+    );
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn assign_to_input_field() {
+    let source = "interface Stream { out valid: bit, in ready: bit }
+        top mod a ( out tx: Stream ) { tx.valid = 1; tx.ready = 1; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::AssignToInput {
+            id: "tx".to_string(),
+            pos: 104
+        })
+    );
+}
+
+#[test]
+fn flipped_non_interface() {
+    let source = "top mod a ( in x: Flipped<bit>, out y: bit ) { y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedInterface { ty: Type::Bit })
+    );
+}
+
+#[test]
+fn mismatched_interface() {
+    let source = "interface Stream { out valid: bit }
+        interface Flag { out valid: bit }
+        mod b ( out tx: Stream ) { tx.valid = 1; }
+        top mod a ( out y: bit ) { let f: Flag; inst i: b ( tx: f ); y = f.valid; }";
+
+    let ast = parse_source(source).unwrap();
+    let ty = |name: &str, pos| Type::Interface {
+        name: name.to_string(),
+        fields: vec![Port {
+            dir: Dir::Output,
+            name: "valid".to_string(),
+            ty: Type::Bit,
+            attrs: vec![],
+            pos,
+        }],
+    };
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedInterface {
+            lty: ty("Flag", 65),
+            rty: ty("Stream", 23),
+            pos: 193,
+        })
+    );
+}
+
+#[test]
+fn memory_declaration() {
+    assert_stmt(
+        "mem(clk) ram: [bits<32>; 1024] = readmemh(\"ram.hex\");",
+        &Stmt::Mem {
+            id: "ram".to_string(),
+            ty: Type::Array {
+                elem: Box::new(Type::Bits { width: 32 }),
+                len: 1024,
+            },
+            clk: "clk".to_string(),
+            init: Some(MemInit::File("ram.hex".to_string())),
+            attrs: vec![],
+            pos: 36,
+        },
+    );
+}
+
+#[test]
+fn memory_write() {
+    assert_stmt(
+        "ram[a] = d if we;",
+        &Stmt::MemWrite {
+            id: "ram".to_string(),
+            addr: Expr::Ident {
+                id: "a".to_string(),
+            },
+            ex: Expr::Ident {
+                id: "d".to_string(),
+            },
+            en: Some(Expr::Ident {
+                id: "we".to_string(),
+            }),
+            pos: 27,
+        },
+    );
+}
+
+#[test]
+fn memory_length() {
+    let source = "top mod a ( in clk: clock, in i: bits<2>, out y: bits<4> ) {
+        mem(clk) rom: [bits<4>; 4] = [1, 2, 3];
+        y = rom[i];
+    }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedLength {
+            expected: 4,
+            found: 3,
+            pos: 78,
+        })
+    );
+}
+
+#[test]
+fn write_to_non_memory() {
+    let source = "top mod a ( in i: bits<2>, out y: bits<4> ) { y[i] = 1; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::NotAMemory {
+            id: "y".to_string(),
+            pos: 46
+        })
+    );
+}
+
+#[test]
+fn memory_as_value() {
+    let source = "top mod a ( in clk: clock, out y: bits<4> ) {
+        mem(clk) ram: [bits<4>; 4];
+        y = ram;
+    }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedData {
+            id: "ram".to_string(),
+            ty: Type::Array {
+                elem: Box::new(Type::Bits { width: 4 }),
+                len: 4,
+            },
+            pos: 90,
+        })
+    );
+}
+
+#[test]
+fn tristate_expr() {
+    assert_expr(
+        "tristate(a, b)",
+        &Expr::Tristate {
+            en: Box::new(Expr::Ident {
+                id: "a".to_string(),
+            }),
+            ex: Box::new(Expr::Ident {
+                id: "b".to_string(),
+            }),
+        },
+    );
+}
+
+#[test]
+fn drive_inout_directly() {
+    let source = "top mod a ( in b: bit, inout y: bit ) { y = b; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(ast.mods[0].ports[1].dir, Dir::InOut);
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedTristate {
+            id: "y".to_string(),
+            pos: 40
+        })
+    );
+}
+
+#[test]
+fn nested_tristate() {
+    let source = "top mod a ( in b: bit, inout y: bit ) { y = tristate(b, b) | b; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedTristate {
+            id: "y".to_string(),
+            pos: 40
+        })
+    );
+}
+
+#[test]
+fn tristate_register() {
+    let source = "top mod a ( in clk: clock, in b: bit, out y: bit ) {
+        reg(clk) r: bit;
+        r = tristate(b, b);
+        y = r;
+    }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Err(Error::UnexpectedTristate { pos: 86 }));
+}
+
+#[test]
+fn inout_to_input() {
+    let source = "mod b ( inout bus: bit ) { bus = tristate(1, 0); }
+        top mod a ( in x: bit ) { inst i: b ( bus: x ); }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::InvalidInOutConnection {
+            id: "i".to_string(),
+            port: "bus".to_string(),
+            pos: 102,
+        })
+    );
+}
+
+#[test]
+fn for_stmt() {
+    assert_stmt(
+        "for i in 0..N { let x#i: bit; }",
+        &Stmt::For {
+            var: "i".to_string(),
+            start: Expr::Litrl {
+                val: BigUint::from(0u32),
+                width: None,
+            },
+            end: Expr::Ident {
+                id: "N".to_string(),
+            },
+            body: vec![Stmt::Declare {
+                id: "x#i".to_string(),
+                ty: Type::Bit,
+                attrs: vec![],
+                pos: 47,
+            }],
+            pos: 36,
+        },
+    );
+}
+
+#[test]
+fn if_stmt() {
+    assert_stmt(
+        "if A { y = 1; } else if B { y = 0; }",
+        &Stmt::If {
+            cond: Expr::Ident {
+                id: "A".to_string(),
+            },
+            tstmts: vec![Stmt::Assign {
+                id: "y".to_string(),
+                fields: vec![],
+                ex: Expr::Litrl {
+                    val: BigUint::from(1u32),
+                    width: None,
+                },
+                pos: 34,
+            }],
+            fstmts: vec![Stmt::If {
+                cond: Expr::Ident {
+                    id: "B".to_string(),
+                },
+                tstmts: vec![Stmt::Assign {
+                    id: "y".to_string(),
+                    fields: vec![],
+                    ex: Expr::Litrl {
+                        val: BigUint::from(0u32),
+                        width: None,
+                    },
+                    pos: 55,
+                }],
+                fstmts: vec![],
+                pos: 51,
+            }],
+            pos: 30,
+        },
+    );
+}
+
+#[test]
+fn unrolled_names() {
+    let source = "top mod a ( in x: bits<4>, out y: bit ) {
+        for i in 0..4 { let b#i: bit = x[i]; }
+        y = b_0 ^ b_3;
+    }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn pruned_branch() {
+    let source = "const WIDE: bit = 0;
+        top mod a ( in x: bits<4>, out y: bit ) {
+            if WIDE { y = missing; } else { y = x[0]; }
+        }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn undeclared_suffix() {
+    let source = "top mod a ( out y: bit ) { let b#j: bit = 0; y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UndeclaredIdentifier {
+            id: "j".to_string(),
+            pos: 31
+        })
+    );
+}
+
+#[test]
+fn function() {
+    let source = "fn inc(x: bits<4>) -> bits<4> { let one: bits<4> = 1; x + one }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.fns,
+        vec![Function {
+            name: "inc".to_string(),
+            params: vec![Field {
+                name: "x".to_string(),
+                ty: Type::Bits { width: 4 },
+                pos: 7,
+            }],
+            ret: Type::Bits { width: 4 },
+            body: vec![Stmt::DeclareAssign {
+                id: "one".to_string(),
+                ty: Type::Bits { width: 4 },
+                ex: Expr::Litrl {
+                    val: BigUint::from(1u32),
+                    width: None,
+                },
+                attrs: vec![],
+                pos: 36,
+            }],
+            result: Expr::Binary {
+                lex: Box::new(Expr::Ident {
+                    id: "x".to_string()
+                }),
+                op: BinaryOp::Add,
+                rex: Box::new(Expr::Ident {
+                    id: "one".to_string()
+                }),
+                pos: 56,
+            },
+            pos: 3,
+        }]
+    );
+}
+
+#[test]
+fn call_expr() {
+    assert_expr(
+        "f(a, 1)",
+        &Expr::Call {
+            name: "f".to_string(),
+            args: vec![
+                Expr::Ident {
+                    id: "a".to_string(),
+                },
+                Expr::Litrl {
+                    val: BigUint::from(1u32),
+                    width: None,
+                },
+            ],
+        },
+    );
+}
+
+#[test]
+fn stateful_function() {
+    let source = "fn f(clk: clock, x: bit) -> bit { reg(clk) r: bit; r }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::StatefulFunction {
+            name: "f".to_string(),
+            pos: 43
+        })
+    );
+}
+
+#[test]
+fn impure_function() {
+    let source = "fn f(x: bit) -> bit { x ^ y }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UndeclaredIdentifier {
+            id: "y".to_string(),
+            pos: 3
+        })
+    );
+}
+
+#[test]
+fn call_beside_local_name() {
+    let source = "fn parity(x: bits<2>) -> bit { ^x }
+        top mod a ( in a: bits<2>, in parity_0_x: bit, out y: bit ) { y = parity(a) ^ parity_0_x; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn mismatched_arguments() {
+    let source = "fn f(x: bit) -> bit { x } top mod a ( in b: bit, out y: bit ) { y = f(b, b); }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::MismatchedArguments {
+            name: "f".to_string(),
+            expected: 1,
+            found: 2,
+            pos: 64,
+        })
+    );
+}
+
+#[test]
+fn recursive_function() {
+    let source = "fn f(x: bit) -> bit { g(x) } fn g(x: bit) -> bit { f(x) }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::RecursiveFunction {
+            name: "f".to_string(),
+            pos: 32
+        })
+    );
+}
+
+#[test]
+fn undeclared_function() {
+    let source = "top mod a ( in b: bit, out y: bit ) { y = f(b); }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UndeclaredFunction {
+            name: "f".to_string(),
+            pos: 38
+        })
+    );
+}
+
+#[test]
+fn type_alias() {
+    let source = "type Word = bits<32>;";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.aliases,
+        vec![Alias {
+            name: "Word".to_string(),
+            ty: Type::Bits { width: 32 },
+        }]
+    );
+}
+
+#[test]
+fn alias_in_error() {
+    let source = "type Count = uint<8>; top mod a ( in b: sint<8>, out y: Count ) { y = b; }";
+
+    let ast = parse_source(source).unwrap();
+
+    let err = check_ast(&ast).unwrap_err();
+    assert!(
+        matches!(&err, Error::MismatchedSignedness { lty: Type::Alias { name, .. }, .. } if name == "Count"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn alias_is_same_type() {
+    let source = "type Word = bits<8>; type Byte = Word; \
+                  top mod a ( in b: bits<8>, out y: Byte ) { let w: Word = b; y = w; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+}
+
+#[test]
+fn alias_before_declaration() {
+    let source = "type Byte = Word; type Word = bits<8>; top mod a ( out y: Byte ) { y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::UndeclaredType {
+            name: "Word".to_string()
+        })
+    );
+}
+
+#[test]
+fn duplicate_alias() {
+    let source = "struct S { a: bit } type S = bit; top mod a ( out y: bit ) { y = 0; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::DuplicateType {
+            name: "S".to_string()
+        })
+    );
+}
+
+#[test]
+fn use_decl() {
+    let source = "use lib::fifo::Fifo;";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.uses,
+        vec![Use {
+            file: vec!["lib".to_string(), "fifo".to_string()],
+            item: "Fifo".to_string(),
+            pos: 4,
+        }]
+    );
+}
+
+#[test]
+fn use_without_file() {
+    let source = "use Fifo;";
+
+    assert!(parse_source(source).is_err());
+}
+
+#[test]
+fn immediate_assert_stmt() {
+    assert_stmt(
+        "assert y;",
+        &Stmt::Assert {
+            kind: Assertion::Assert,
+            ex: Expr::Ident {
+                id: "y".to_string(),
+            },
+            clk: None,
+            rst: None,
+            pos: 34,
+        },
+    );
+}
+
+#[test]
+fn clocked_cover_stmt() {
+    assert_stmt(
+        "cover @(clk, rst) y;",
+        &Stmt::Assert {
+            kind: Assertion::Cover,
+            ex: Expr::Ident {
+                id: "y".to_string(),
+            },
+            clk: Some("clk".to_string()),
+            rst: Some("rst".to_string()),
+            pos: 45,
+        },
+    );
+}
+
+#[test]
+fn assert_on_non_clock() {
+    let source = "top mod a ( in c: bit, out y: bit ) { y = c; assume @(c) y; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedClock {
+            ty: Type::Bit,
+            pos: 57
+        })
+    );
+}
+
+#[test]
+fn assert_non_bit() {
+    let source = "top mod a ( in b: bits<2>, out y: bit ) { y = 0; assert b; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        check_ast(&ast),
+        Err(Error::ExpectedBit {
+            ty: Type::Bits { width: 2 },
+            pos: 56
+        })
+    );
+}
+
+#[test]
+fn attributed_reg() {
+    assert_stmt(
+        "#[async_reg] #[keep] reg(clk) q: bit;",
+        &Stmt::Reg {
+            id: "q".to_string(),
+            ty: Type::Bit,
+            clk: "clk".to_string(),
+            rst: None,
+            init: None,
+            attrs: vec![
+                Attr {
+                    name: "async_reg".to_string(),
+                    value: None,
+                    pos: 29,
+                },
+                Attr {
+                    name: "keep".to_string(),
+                    value: None,
+                    pos: 42,
+                },
+            ],
+            pos: 57,
+        },
+    );
+}
+
+#[test]
+fn attributed_module_and_ports() {
+    let source = "#[keep] top mod a ( #[mark_debug(yes)] in x: bit, out y: bit ) { y = x; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(
+        ast.mods[0].attrs,
+        vec![Attr {
+            name: "keep".to_string(),
+            value: None,
+            pos: 2,
+        }]
+    );
+    assert_eq!(
+        ast.mods[0].ports[0].attrs,
+        vec![Attr {
+            name: "mark_debug".to_string(),
+            value: Some("yes".to_string()),
+            pos: 22,
+        }]
+    );
+    assert!(ast.mods[0].ports[1].attrs.is_empty());
+    assert_eq!(check_ast(&ast), Ok(()));
+    assert_eq!(warnings(&ast), vec![]);
+}
+
+#[test]
+fn unknown_attribute() {
+    let source = "top mod a ( out y: bit ) { #[fast] let x: bit = 1; y = x; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+    assert_eq!(
+        warnings(&ast),
+        vec![Warning::UnknownAttribute {
+            name: "fast".to_string(),
+            pos: 29,
+        }]
+    );
+}
+
+#[test]
+fn unknown_enum_attribute() {
+    let source = "#[packed] #[encoding(gray)] enum State { Idle, Busy }
+        top mod a ( out y: bit ) { y = 1; }";
+
+    let ast = parse_source(source).unwrap();
+
+    assert_eq!(check_ast(&ast), Ok(()));
+    assert_eq!(
+        warnings(&ast),
+        vec![Warning::UnknownAttribute {
+            name: "packed".to_string(),
+            pos: 2,
+        }]
+    );
+}
+
 #[test]
 fn precedence() {
     assert_expr(
         "1 | 2 ^ 3 & & 4 << 5",
         &Expr::Binary {
             lex: Box::new(Expr::Litrl {
-                val: "1".to_string(),
+                val: BigUint::from(1u32),
+                width: None,
             }),
             op: BinaryOp::BitOr,
             rex: Box::new(Expr::Binary {
                 lex: Box::new(Expr::Litrl {
-                    val: "2".to_string(),
+                    val: BigUint::from(2u32),
+                    width: None,
                 }),
                 op: BinaryOp::BitXor,
                 rex: Box::new(Expr::Binary {
                     lex: Box::new(Expr::Litrl {
-                        val: "3".to_string(),
+                        val: BigUint::from(3u32),
+                        width: None,
                     }),
                     op: BinaryOp::BitAnd,
                     rex: Box::new(Expr::Binary {
                         lex: Box::new(Expr::Unary {
                             op: UnaryOp::ReductAnd,
                             ex: Box::new(Expr::Litrl {
-                                val: "4".to_string(),
+                                val: BigUint::from(4u32),
+                                width: None,
                             }),
                         }),
                         op: BinaryOp::ShiftLeft,
                         rex: Box::new(Expr::Litrl {
-                            val: "5".to_string(),
+                            val: BigUint::from(5u32),
+                            width: None,
                         }),
+                        pos: 47,
                     }),
+                    pos: 41,
                 }),
+                pos: 37,
             }),
+            pos: 33,
         },
     );
 }
@@ -103,25 +2099,32 @@ fn paren_grouping() {
         &Expr::Binary {
             lex: Box::new(Expr::Binary {
                 lex: Box::new(Expr::Litrl {
-                    val: "1".to_string(),
+                    val: BigUint::from(1u32),
+                    width: None,
                 }),
                 op: BinaryOp::ShiftLeft,
                 rex: Box::new(Expr::Paren {
                     ex: Box::new(Expr::Binary {
                         lex: Box::new(Expr::Litrl {
-                            val: "2".to_string(),
+                            val: BigUint::from(2u32),
+                            width: None,
                         }),
                         op: BinaryOp::BitOr,
                         rex: Box::new(Expr::Litrl {
-                            val: "3".to_string(),
+                            val: BigUint::from(3u32),
+                            width: None,
                         }),
+                        pos: 40,
                     }),
                 }),
+                pos: 33,
             }),
             op: BinaryOp::ShiftRight,
             rex: Box::new(Expr::Litrl {
-                val: "1".to_string(),
+                val: BigUint::from(1u32),
+                width: None,
             }),
+            pos: 46,
         },
     );
 }
@@ -138,7 +2141,7 @@ fn trailing_comma() {
     let comma = "top mod a (
             in x: bit,
             out y: bit,
-        ) {
+       ) {
             y = x;
         }";
 
@@ -159,20 +2162,36 @@ fn module() {
     let ast = parse_source(source).unwrap();
 
     let expected = Ast {
-        top: Mod {
+        uses: vec![],
+        consts: vec![],
+        enums: vec![],
+        structs: vec![],
+        interfaces: vec![],
+        fns: vec![],
+        aliases: vec![],
+        mods: vec![Mod {
             name: "a".to_string(),
+            top: true,
+            params: vec![],
             ports: vec![Port {
                 dir: Dir::Output,
                 name: "y".to_string(),
                 ty: Type::Bit,
+                attrs: vec![],
+                pos: 28,
             }],
             stmts: vec![Stmt::Assign {
                 id: "y".to_string(),
+                fields: vec![],
                 ex: Expr::Litrl {
-                    val: "1".to_string(),
+                    val: BigUint::from(1u32),
+                    width: None,
                 },
+                pos: 59,
             }],
-        },
+            attrs: vec![],
+            pos: 8,
+        }],
     };
 
     assert_eq!(ast, expected);
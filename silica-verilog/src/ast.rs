@@ -1,19 +1,29 @@
 use std::fmt;
 
+use num_bigint::BigUint;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Ast {
-    pub top: Mod,
+    pub mods: Vec<Mod>,
 }
 
 impl fmt::Display for Ast {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.top)
+        for (idx, m) in self.mods.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", m)?;
+        }
+        Ok(())
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Mod {
     pub name: String,
+    pub attrs: Vec<Attr>,
+    pub params: Vec<Param>,
     pub ports: Vec<Port>,
     pub stmts: Vec<Stmt>,
 }
@@ -35,23 +45,76 @@ impl fmt::Display for Mod {
             stmts.push_str(format!("\t{} \n", stmt).as_str());
         }
 
+        let mut params = String::new();
+        if !self.params.is_empty() {
+            params.push_str("#( \n");
+            for (idx, param) in self.params.iter().enumerate() {
+                if idx == (self.params.len() - 1) {
+                    params.push_str(format!("\t{} \n", param).as_str());
+                } else {
+                    params.push_str(format!("\t{}, \n", param).as_str());
+                }
+            }
+            params.push_str(") ");
+        }
+
+        write_attrs(f, &self.attrs)?;
         write!(
             f,
-            "module {} ( \n{}); \n{}endmodule \n",
-            self.name, ports, stmts
+            "module {} {}( \n{}); \n{}endmodule \n",
+            self.name, params, ports, stmts
         )
     }
 }
 
+/// An attribute instance, passing a property of an item on to tools.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attr {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl fmt::Display for Attr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{} = \"{}\"", self.name, value),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Writes attributes ahead of the item they belong to, if there are any.
+fn write_attrs(f: &mut fmt::Formatter, attrs: &[Attr]) -> fmt::Result {
+    if attrs.is_empty() {
+        return Ok(());
+    }
+    let attrs: Vec<String> = attrs.iter().map(|a| a.to_string()).collect();
+    write!(f, "(* {} *) ", attrs.join(", "))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub default: Expr,
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "parameter {} = {}", self.name, self.default)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Port {
     pub dir: Dir,
     pub name: String,
     pub ty: Type,
+    pub attrs: Vec<Attr>,
 }
 
 impl fmt::Display for Port {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_attrs(f, &self.attrs)?;
         write!(f, "{} {} {}", self.dir, self.ty, self.name)
     }
 }
@@ -60,6 +123,7 @@ impl fmt::Display for Port {
 pub enum Dir {
     Input,
     Output,
+    InOut,
 }
 
 impl fmt::Display for Dir {
@@ -70,6 +134,7 @@ impl fmt::Display for Dir {
             match self {
                 Dir::Input => "input",
                 Dir::Output => "output",
+                Dir::InOut => "inout",
             }
         )
     }
@@ -77,26 +142,98 @@ impl fmt::Display for Dir {
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Type {
-    Wire,
+    Wire { signed: bool, width: Option<Expr> },
+    Reg { signed: bool, width: Option<Expr> },
 }
 
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Type::Wire => "wire",
+        let (signed, width) = match self {
+            Type::Wire { signed, width } => {
+                write!(f, "wire")?;
+                (signed, width)
             }
-        )
+            Type::Reg { signed, width } => {
+                write!(f, "reg")?;
+                (signed, width)
+            }
+        };
+        write_shape(f, *signed, width)
+    }
+}
+
+/// Writes the signedness and range of a declaration.
+fn write_shape(f: &mut fmt::Formatter, signed: bool, width: &Option<Expr>) -> fmt::Result {
+    if signed {
+        write!(f, " signed")?;
+    }
+    match width {
+        Some(Expr::Int { val }) => write!(f, " [{}:0]", val - 1),
+        Some(width @ (Expr::Ident { .. } | Expr::Paren { .. })) => write!(f, " [{}-1:0]", width),
+        // any operator in the width must bind ahead of the subtraction
+        Some(width) => write!(f, " [({})-1:0]", width),
+        None => Ok(()),
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Stmt {
-    Assign { id: String, ex: Expr },
-    Declare { id: String, ty: Type },
-    DeclareAssign { id: String, ty: Type, ex: Expr },
+    Assign {
+        id: String,
+        ex: Expr,
+    },
+    Declare {
+        id: String,
+        ty: Type,
+    },
+    DeclareAssign {
+        id: String,
+        ty: Type,
+        ex: Expr,
+    },
+    LocalParam {
+        id: String,
+        signed: bool,
+        width: Option<Expr>,
+        ex: Expr,
+    },
+    Always {
+        event: Event,
+        body: Vec<Proc>,
+    },
+    /// A memory of words of type `ty`, addressed from zero to `last`.
+    Memory {
+        id: String,
+        ty: Type,
+        last: Expr,
+    },
+    Initial {
+        body: Vec<Proc>,
+    },
+    Inst {
+        id: String,
+        module: String,
+        params: Vec<Conn>,
+        conns: Vec<Conn>,
+    },
+    /// A concurrent assertion, checked on every rising edge of `clk` while
+    /// `rst` isn't set.
+    Property {
+        kind: Assertion,
+        clk: String,
+        rst: Option<String>,
+        ex: Expr,
+    },
+    /// Statements only seen by tools that define the macro `name`.
+    IfDef {
+        name: String,
+        stmts: Vec<Stmt>,
+    },
+    /// A declaration along with the attributes given to it.
+    Attributed {
+        attrs: Vec<Attr>,
+        stmt: Box<Stmt>,
+    },
 }
 
 impl fmt::Display for Stmt {
@@ -105,10 +242,238 @@ impl fmt::Display for Stmt {
             Stmt::Assign { id, ex } => write!(f, "assign {} = {};", id, ex),
             Stmt::Declare { id, ty } => write!(f, "{} {};", ty, id),
             Stmt::DeclareAssign { id, ty, ex } => write!(f, "{} {} = {};", ty, id, ex),
+            Stmt::LocalParam {
+                id,
+                signed,
+                width,
+                ex,
+            } => {
+                write!(f, "localparam")?;
+                write_shape(f, *signed, width)?;
+                write!(f, " {} = {};", id, ex)
+            }
+            Stmt::Always { event, body } => {
+                writeln!(f, "always {} begin ", event)?;
+                for stmt in body {
+                    stmt.fmt_indented(f, 2)?;
+                }
+                write!(f, "\tend")
+            }
+            Stmt::Memory { id, ty, last } => write!(f, "{} {} [0:{}];", ty, id, last),
+            Stmt::Initial { body } => {
+                writeln!(f, "initial begin ")?;
+                for stmt in body {
+                    stmt.fmt_indented(f, 2)?;
+                }
+                write!(f, "\tend")
+            }
+            Stmt::Inst {
+                id,
+                module,
+                params,
+                conns,
+            } => {
+                write!(f, "{} ", module)?;
+                if !params.is_empty() {
+                    let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                    write!(f, "#({}) ", params.join(", "))?;
+                }
+                writeln!(f, "{} ( ", id)?;
+                for (idx, conn) in conns.iter().enumerate() {
+                    if idx == conns.len() - 1 {
+                        writeln!(f, "\t\t{} ", conn)?;
+                    } else {
+                        writeln!(f, "\t\t{}, ", conn)?;
+                    }
+                }
+                write!(f, "\t);")
+            }
+            Stmt::Property { kind, clk, rst, ex } => {
+                write!(f, "{} property (@(posedge {}) ", kind, clk)?;
+                if let Some(rst) = rst {
+                    write!(f, "disable iff ({}) ", rst)?;
+                }
+                write!(f, "{});", ex)
+            }
+            Stmt::IfDef { name, stmts } => {
+                writeln!(f, "`ifdef {} ", name)?;
+                for stmt in stmts {
+                    writeln!(f, "\t{} ", stmt)?;
+                }
+                write!(f, "\t`endif")
+            }
+            Stmt::Attributed { attrs, stmt } => {
+                write_attrs(f, attrs)?;
+                write!(f, "{}", stmt)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Assertion {
+    Assert,
+    Assume,
+    Cover,
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Assertion::Assert => "assert",
+                Assertion::Assume => "assume",
+                Assertion::Cover => "cover",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conn {
+    pub port: String,
+    pub ex: Expr,
+}
+
+impl fmt::Display for Conn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, ".{}({})", self.port, self.ex)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Any,
+    Posedge { ids: Vec<String> },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Any => write!(f, "@*"),
+            Event::Posedge { ids } => {
+                let edges: Vec<String> = ids.iter().map(|id| format!("posedge {}", id)).collect();
+                write!(f, "@({})", edges.join(" or "))
+            }
+        }
+    }
+}
+
+/// Procedural statements, found within `always` blocks.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Proc {
+    Assign {
+        id: String,
+        ex: Expr,
+    },
+    NonBlockingAssign {
+        id: String,
+        ex: Expr,
+    },
+    /// Assigns one word of a memory.
+    IndexAssign {
+        id: String,
+        idx: Expr,
+        ex: Expr,
+    },
+    IndexNonBlockingAssign {
+        id: String,
+        idx: Expr,
+        ex: Expr,
+    },
+    /// Loads a memory from a file of hexadecimal words.
+    ReadMemH {
+        file: String,
+        id: String,
+    },
+    /// An immediate assertion, checked whenever the statement runs.
+    Assert {
+        kind: Assertion,
+        ex: Expr,
+    },
+    If {
+        cond: Expr,
+        tstmts: Vec<Proc>,
+        fstmts: Vec<Proc>,
+    },
+    Case {
+        ex: Expr,
+        items: Vec<CaseItem>,
+        default: Box<Proc>,
+    },
+}
+
+impl Proc {
+    /// Writes the statement on its own lines, indented by `depth` tabs.
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let indent = "\t".repeat(depth);
+        match self {
+            Proc::Assign { .. }
+            | Proc::NonBlockingAssign { .. }
+            | Proc::IndexAssign { .. }
+            | Proc::IndexNonBlockingAssign { .. }
+            | Proc::ReadMemH { .. }
+            | Proc::Assert { .. } => writeln!(f, "{}{} ", indent, self),
+            Proc::If {
+                cond,
+                tstmts,
+                fstmts,
+            } => {
+                writeln!(f, "{}if ({}) begin ", indent, cond)?;
+                for stmt in tstmts {
+                    stmt.fmt_indented(f, depth + 1)?;
+                }
+                if !fstmts.is_empty() {
+                    writeln!(f, "{}end else begin ", indent)?;
+                    for stmt in fstmts {
+                        stmt.fmt_indented(f, depth + 1)?;
+                    }
+                }
+                writeln!(f, "{}end ", indent)
+            }
+            Proc::Case { ex, items, default } => {
+                writeln!(f, "{}case ({}) ", indent, ex)?;
+                for item in items {
+                    writeln!(f, "{}\t{} ", indent, item)?;
+                }
+                writeln!(f, "{}\tdefault: {} ", indent, default)?;
+                writeln!(f, "{}endcase ", indent)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Proc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Proc::Assign { id, ex } => write!(f, "{} = {};", id, ex),
+            Proc::NonBlockingAssign { id, ex } => write!(f, "{} <= {};", id, ex),
+            Proc::IndexAssign { id, idx, ex } => write!(f, "{}[{}] = {};", id, idx, ex),
+            Proc::IndexNonBlockingAssign { id, idx, ex } => {
+                write!(f, "{}[{}] <= {};", id, idx, ex)
+            }
+            Proc::ReadMemH { file, id } => write!(f, "$readmemh(\"{}\", {});", file, id),
+            Proc::Assert { kind, ex } => write!(f, "{} ({});", kind, ex),
+            Proc::If { .. } | Proc::Case { .. } => self.fmt_indented(f, 0),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaseItem {
+    pub labels: Vec<Expr>,
+    pub body: Proc,
+}
+
+impl fmt::Display for CaseItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let labels: Vec<String> = self.labels.iter().map(|ex| ex.to_string()).collect();
+        write!(f, "{}: {}", labels.join(", "), self.body)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
     Binary {
@@ -123,11 +488,41 @@ pub enum Expr {
     Paren {
         ex: Box<Expr>,
     },
+    Ternary {
+        cond: Box<Expr>,
+        tex: Box<Expr>,
+        fex: Box<Expr>,
+    },
+    Index {
+        ex: Box<Expr>,
+        idx: Box<Expr>,
+    },
+    Slice {
+        ex: Box<Expr>,
+        msb: Box<Expr>,
+        lsb: Box<Expr>,
+    },
+    Concat {
+        exs: Vec<Expr>,
+    },
+    Repeat {
+        count: Box<Expr>,
+        ex: Box<Expr>,
+    },
     Ident {
         id: String,
     },
     Litrl {
-        val: String,
+        val: BigUint,
+        width: usize,
+        signed: bool,
+    },
+    Int {
+        val: usize,
+    },
+    /// High impedance across every bit.
+    HighZ {
+        width: usize,
     },
 }
 
@@ -137,8 +532,20 @@ impl fmt::Display for Expr {
             Expr::Binary { lex, op, rex } => write!(f, "{} {} {}", lex, op, rex),
             Expr::Unary { op, ex } => write!(f, "{}{}", op, ex),
             Expr::Paren { ex } => write!(f, "( {} )", ex),
+            Expr::Ternary { cond, tex, fex } => write!(f, "{} ? {} : {}", cond, tex, fex),
+            Expr::Index { ex, idx } => write!(f, "{}[{}]", ex, idx),
+            Expr::Slice { ex, msb, lsb } => write!(f, "{}[{}:{}]", ex, msb, lsb),
+            Expr::Concat { exs } => {
+                let exs: Vec<String> = exs.iter().map(|ex| ex.to_string()).collect();
+                write!(f, "{{{}}}", exs.join(", "))
+            }
+            Expr::Repeat { count, ex } => write!(f, "{{{}{}}}", count, ex),
             Expr::Ident { id } => write!(f, "{}", id),
-            Expr::Litrl { val } => write!(f, "{}", val),
+            Expr::Litrl { val, width, signed } => {
+                write!(f, "{}'{}h{:X}", width, if *signed { "s" } else { "" }, val)
+            }
+            Expr::Int { val } => write!(f, "{}", val),
+            Expr::HighZ { width } => write!(f, "{}'bz", width),
         }
     }
 }
@@ -147,9 +554,41 @@ impl fmt::Display for Expr {
 pub enum BinaryOp {
     ShiftLeft,
     ShiftRight,
+    ShiftRightArith,
     BitAnd,
     BitXor,
     BitOr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    LogicAnd,
+    LogicOr,
+}
+
+impl BinaryOp {
+    /// Verilog operator precedence, where higher values bind more tightly.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 10,
+            BinaryOp::Add | BinaryOp::Sub => 9,
+            BinaryOp::ShiftLeft | BinaryOp::ShiftRight | BinaryOp::ShiftRightArith => 8,
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => 7,
+            BinaryOp::Equal | BinaryOp::NotEqual => 6,
+            BinaryOp::BitAnd => 5,
+            BinaryOp::BitXor => 4,
+            BinaryOp::BitOr => 3,
+            BinaryOp::LogicAnd => 2,
+            BinaryOp::LogicOr => 1,
+        }
+    }
 }
 
 impl fmt::Display for BinaryOp {
@@ -160,9 +599,23 @@ impl fmt::Display for BinaryOp {
             match self {
                 BinaryOp::ShiftLeft => "<<",
                 BinaryOp::ShiftRight => ">>",
+                BinaryOp::ShiftRightArith => ">>>",
                 BinaryOp::BitAnd => "&",
                 BinaryOp::BitXor => "^",
                 BinaryOp::BitOr => "|",
+                BinaryOp::Add => "+",
+                BinaryOp::Sub => "-",
+                BinaryOp::Mul => "*",
+                BinaryOp::Div => "/",
+                BinaryOp::Rem => "%",
+                BinaryOp::Equal => "==",
+                BinaryOp::NotEqual => "!=",
+                BinaryOp::Less => "<",
+                BinaryOp::LessEqual => "<=",
+                BinaryOp::Greater => ">",
+                BinaryOp::GreaterEqual => ">=",
+                BinaryOp::LogicAnd => "&&",
+                BinaryOp::LogicOr => "||",
             }
         )
     }
@@ -171,6 +624,7 @@ impl fmt::Display for BinaryOp {
 #[derive(Clone, Debug, PartialEq)]
 pub enum UnaryOp {
     Negate,
+    LogicNot,
     ReductAnd,
     ReductXor,
     ReductOr,
@@ -183,6 +637,7 @@ impl fmt::Display for UnaryOp {
             "{}",
             match self {
                 UnaryOp::Negate => "~",
+                UnaryOp::LogicNot => "!",
                 UnaryOp::ReductAnd => "&",
                 UnaryOp::ReductXor => "^",
                 UnaryOp::ReductOr => "|",
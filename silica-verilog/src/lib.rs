@@ -2,17 +2,40 @@ mod ast;
 mod transform;
 
 pub struct Verilog {
-    ast: ast::Ast,
+    ast: syntax::ast::Ast,
+    monomorphize: bool,
 }
 
 impl Verilog {
     pub fn new(ast: &syntax::ast::Ast) -> Verilog {
         Verilog {
-            ast: transform::transform_ast(ast),
+            ast: ast.clone(),
+            monomorphize: false,
         }
     }
 
+    /// Generates a separate module for every set of parameter values in use,
+    /// rather than emitting Verilog parameters. Designs with generation blocks
+    /// or intermediate signals that depend on parameters are always generated
+    /// this way.
+    pub fn monomorphize(&mut self, monomorphize: bool) -> &mut Verilog {
+        self.monomorphize = monomorphize;
+        self
+    }
+
     pub fn build(&mut self) -> String {
-        format!("{}", self.ast)
+        let ast = syntax::elab::resolve_aliases(&self.ast);
+        let ast = if self.monomorphize
+            || transform::has_generic_blocks(&ast)
+            || transform::has_generic_temps(&ast)
+        {
+            let ast = syntax::elab::monomorphize(&ast)
+                .expect("parameters should be checked before lowering");
+            transform::transform_ast(&ast)
+        } else {
+            transform::transform_ast(&ast)
+        };
+
+        format!("{}", ast)
     }
 }
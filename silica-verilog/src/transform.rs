@@ -1,24 +1,592 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::ast::*;
 
+use num_bigint::{BigInt, Sign};
+use num_traits::ToPrimitive;
+use syntax::check::{Reg, Scope};
+use syntax::elab::{expand_defaults, resolve_defaults};
+use syntax::eval::{to_expr, Value};
+use syntax::visit::{walk_expr, Visitor};
+
 pub fn transform_ast(a: &syntax::ast::Ast) -> Ast {
     Ast {
-        top: transform_mod(&a.top),
+        mods: a.mods.iter().map(|m| transform_mod(a, m)).collect(),
+    }
+}
+
+/// State threaded through the lowering of a single module.
+struct Context<'a> {
+    ast: &'a syntax::ast::Ast,
+    scope: Scope,
+    /// Parameters of the module, which are kept symbolic.
+    params: &'a [syntax::ast::Param],
+    /// Constants visible within the module, as declared.
+    consts: HashMap<&'a str, &'a syntax::ast::Const>,
+    /// Supporting statements that must be emitted ahead of the statement
+    /// currently being lowered.
+    hoisted: Vec<Stmt>,
+    /// Registers given a next value somewhere in the module.
+    assigned: HashSet<String>,
+    temps: usize,
+}
+
+impl<'a> Context<'a> {
+    /// Generates a fresh signal name that doesn't clash with any declared one.
+    fn temp(&mut self, prefix: &str) -> String {
+        loop {
+            let id = format!("{}_{}", prefix, self.temps);
+            self.temps += 1;
+            if !self.scope.is_declared(&id) {
+                return id;
+            }
+        }
+    }
+
+    /// Whether the value of an expression depends on a parameter, so it
+    /// can't be replaced by its value under the defaults.
+    fn is_generic(&self, e: &syntax::ast::Expr) -> bool {
+        depends_on_params(e, self.params, &self.consts)
+    }
+}
+
+fn depends_on_params(
+    e: &syntax::ast::Expr,
+    params: &[syntax::ast::Param],
+    consts: &HashMap<&str, &syntax::ast::Const>,
+) -> bool {
+    struct Idents(Vec<String>);
+
+    impl Visitor for Idents {
+        fn visit_expr(&mut self, e: &syntax::ast::Expr) {
+            if let syntax::ast::Expr::Ident { id } = e {
+                self.0.push(id.clone());
+            }
+            walk_expr(self, e);
+        }
     }
+
+    let mut idents = Idents(Vec::new());
+    idents.visit_expr(e);
+    idents.0.iter().any(|id| {
+        params.iter().any(|p| p.name == *id)
+            || consts
+                .get(id.as_str())
+                .is_some_and(|c| depends_on_params(&c.ex, params, consts))
+    })
+}
+
+/// Whether a module unrolls differently depending on its parameters, so it
+/// can't be emitted once for every set of parameter values.
+pub fn has_generic_blocks(a: &syntax::ast::Ast) -> bool {
+    a.mods.iter().any(|m| {
+        let consts = module_consts(a, m);
+        block_bounds(&m.stmts)
+            .into_iter()
+            .any(|e| depends_on_params(e, &m.params, &consts))
+    })
+}
+
+/// Whether lowering a module needs an intermediate signal, such as the result
+/// of a match, whose width depends on its parameters. Such a signal can only be
+/// declared at its width under the defaults, so the module can't be emitted
+/// once for every set of parameter values either.
+pub fn has_generic_temps(a: &syntax::ast::Ast) -> bool {
+    a.mods.iter().filter(|m| !m.params.is_empty()).any(|m| {
+        let consts = module_consts(a, m);
+        let stmts = expand_defaults(a, m).expect("parameters should be checked before lowering");
+        let generic = generic_names(m, &stmts, &consts);
+        let memories = stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                syntax::ast::Stmt::Mem { id, .. } if generic.contains(id) => Some(id.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut temps = GenericTemps {
+            generic,
+            memories,
+            found: false,
+        };
+        for stmt in &stmts {
+            temps.visit_stmt(stmt);
+        }
+        temps.found
+    })
 }
 
-fn transform_mod(m: &syntax::ast::Mod) -> Mod {
+/// Finds the expressions lowered into intermediate signals that refer to a
+/// name whose type or value depends on a parameter.
+struct GenericTemps {
+    generic: HashSet<String>,
+    /// Memories whose words are sized by a parameter.
+    memories: HashSet<String>,
+    found: bool,
+}
+
+impl GenericTemps {
+    fn check(&mut self, e: &syntax::ast::Expr) {
+        self.found |= mentions(e, &self.generic);
+    }
+
+    fn check_operand(&mut self, e: &syntax::ast::Expr) {
+        if has_carry(e) {
+            self.check(e);
+        }
+    }
+}
+
+impl Visitor for GenericTemps {
+    fn visit_expr(&mut self, e: &syntax::ast::Expr) {
+        match e {
+            syntax::ast::Expr::Match { .. } => self.check(e),
+            // only the words of a memory size its read register
+            syntax::ast::Expr::Index { ex, .. } => {
+                if let syntax::ast::Expr::Ident { id } = ex.as_ref() {
+                    self.found |= self.memories.contains(id);
+                }
+            }
+            syntax::ast::Expr::Binary { lex, op, rex, .. } => match op {
+                syntax::ast::BinaryOp::Equal
+                | syntax::ast::BinaryOp::NotEqual
+                | syntax::ast::BinaryOp::Less
+                | syntax::ast::BinaryOp::LessEqual
+                | syntax::ast::BinaryOp::Greater
+                | syntax::ast::BinaryOp::GreaterEqual => {
+                    self.check_operand(lex);
+                    self.check_operand(rex);
+                }
+                syntax::ast::BinaryOp::ShiftLeft | syntax::ast::BinaryOp::ShiftRight => {
+                    self.check_operand(lex)
+                }
+                _ => {}
+            },
+            syntax::ast::Expr::Unary { op, ex } if *op != syntax::ast::UnaryOp::Negate => {
+                self.check_operand(ex)
+            }
+            syntax::ast::Expr::Concat { exs } => {
+                for ex in exs {
+                    self.check_operand(ex);
+                }
+            }
+            syntax::ast::Expr::Repeat { ex, .. } => self.check_operand(ex),
+            _ => {}
+        }
+        walk_expr(self, e);
+    }
+}
+
+/// The names within a module whose type or value depends on its parameters:
+/// the parameters themselves, constants computed from them, and the signals
+/// and memories declared with widths in terms of them.
+fn generic_names(
+    m: &syntax::ast::Mod,
+    stmts: &[syntax::ast::Stmt],
+    consts: &HashMap<&str, &syntax::ast::Const>,
+) -> HashSet<String> {
+    let is_generic = |ty: &syntax::ast::Type| is_generic_type(ty, &m.params, consts);
+    let mut names: HashSet<String> = m.params.iter().map(|p| p.name.clone()).collect();
+    names.extend(
+        consts
+            .values()
+            .filter(|c| depends_on_params(&c.ex, &m.params, consts))
+            .map(|c| c.name.clone()),
+    );
+    names.extend(
+        m.ports
+            .iter()
+            .filter(|p| is_generic(&p.ty))
+            .map(|p| p.name.clone()),
+    );
+    for stmt in stmts {
+        match stmt {
+            syntax::ast::Stmt::Declare { id, ty, .. }
+            | syntax::ast::Stmt::DeclareAssign { id, ty, .. }
+            | syntax::ast::Stmt::Reg { id, ty, .. }
+            | syntax::ast::Stmt::Mem { id, ty, .. }
+                if is_generic(ty) =>
+            {
+                names.insert(id.clone());
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Whether the width of a type, or of the words of an array, depends on a
+/// parameter.
+fn is_generic_type(
+    ty: &syntax::ast::Type,
+    params: &[syntax::ast::Param],
+    consts: &HashMap<&str, &syntax::ast::Const>,
+) -> bool {
+    match ty.unaliased() {
+        syntax::ast::Type::Generic { width, .. } => depends_on_params(width, params, consts),
+        syntax::ast::Type::Array { elem, .. } | syntax::ast::Type::GenericArray { elem, .. } => {
+            is_generic_type(elem, params, consts)
+        }
+        _ => false,
+    }
+}
+
+/// Whether an expression refers to any of some names.
+fn mentions(e: &syntax::ast::Expr, names: &HashSet<String>) -> bool {
+    struct Mentions<'a> {
+        names: &'a HashSet<String>,
+        found: bool,
+    }
+
+    impl<'a> Visitor for Mentions<'a> {
+        fn visit_expr(&mut self, e: &syntax::ast::Expr) {
+            match e {
+                syntax::ast::Expr::Ident { id } if self.names.contains(id) => self.found = true,
+                _ => walk_expr(self, e),
+            }
+        }
+    }
+
+    let mut mentions = Mentions {
+        names,
+        found: false,
+    };
+    mentions.visit_expr(e);
+    mentions.found
+}
+
+/// The loop bounds and conditions of the generation blocks among some
+/// statements.
+fn block_bounds(stmts: &[syntax::ast::Stmt]) -> Vec<&syntax::ast::Expr> {
+    stmts
+        .iter()
+        .flat_map(|stmt| match stmt {
+            syntax::ast::Stmt::For {
+                start, end, body, ..
+            } => {
+                let mut bounds = vec![start, end];
+                bounds.extend(block_bounds(body));
+                bounds
+            }
+            syntax::ast::Stmt::If {
+                cond,
+                tstmts,
+                fstmts,
+                ..
+            } => {
+                let mut bounds = vec![cond];
+                bounds.extend(block_bounds(tstmts));
+                bounds.extend(block_bounds(fstmts));
+                bounds
+            }
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// The constants visible within a module, as declared.
+fn module_consts<'a>(
+    a: &'a syntax::ast::Ast,
+    m: &'a syntax::ast::Mod,
+) -> HashMap<&'a str, &'a syntax::ast::Const> {
+    let mut consts: HashMap<&str, &syntax::ast::Const> =
+        a.consts.iter().map(|c| (c.name.as_str(), c)).collect();
+    for stmt in &m.stmts {
+        if let syntax::ast::Stmt::Const(c) = stmt {
+            consts.insert(&c.name, c);
+        }
+    }
+    consts
+}
+
+fn transform_mod(a: &syntax::ast::Ast, m: &syntax::ast::Mod) -> Mod {
+    // Expressions are typed using the default parameter values, while
+    // declarations keep their widths in terms of the parameters.
+    let resolved = resolve_defaults(a, m).expect("parameters should be checked before lowering");
+    let expanded = expand_defaults(a, m).expect("parameters should be checked before lowering");
+    let mut cx = Context {
+        ast: a,
+        scope: Scope::new(a, &resolved).expect("module should be checked before lowering"),
+        params: &m.params,
+        consts: module_consts(a, m),
+        hoisted: Vec::new(),
+        assigned: expanded
+            .iter()
+            .filter_map(|stmt| match stmt {
+                syntax::ast::Stmt::Assign { id, .. } => Some(id.clone()),
+                _ => None,
+            })
+            .collect(),
+        temps: 0,
+    };
+
+    // File constants referred to by name are declared within the module, as
+    // are the variants of any enums used. Constant arrays only initialize
+    // memories, so they are substituted instead.
+    let mut stmts: Vec<Stmt> = a
+        .consts
+        .iter()
+        .filter(|c| c.ty.as_ref().is_some_and(|ty| !ty.is_array()) && refers_to(&expanded, &c.name))
+        .map(|c| transform_localparam(&cx, c))
+        .collect();
+    for e in a.enums.iter().filter(|e| uses_enum(&expanded, &e.name)) {
+        stmts.extend(transform_enum(e));
+    }
+    let mut formal = Vec::new();
+    for stmt in &expanded {
+        let mut lowered = transform_stmt(&mut cx, stmt);
+        stmts.append(&mut cx.hoisted);
+        match stmt {
+            syntax::ast::Stmt::Assert { .. } => formal.append(&mut lowered),
+            _ => stmts.append(&mut lowered),
+        }
+    }
+
+    // Properties are gathered at the end, where tools that don't understand
+    // them can skip them all at once.
+    if !formal.is_empty() {
+        stmts.push(Stmt::IfDef {
+            name: "FORMAL".to_string(),
+            stmts: formal,
+        });
+    }
+
     Mod {
         name: m.name.clone(),
-        ports: m.ports.iter().map(|port| transform_port(port)).collect(),
-        stmts: m.stmts.iter().map(|stmt| transform_stmt(stmt)).collect(),
+        attrs: transform_attrs(&m.attrs),
+        params: m.params.iter().map(|p| transform_param(&cx, p)).collect(),
+        ports: m
+            .ports
+            .iter()
+            .flat_map(|p| transform_port(&cx, p))
+            .collect(),
+        stmts,
+    }
+}
+
+/// Whether any of some statements refers to an identifier.
+fn refers_to(stmts: &[syntax::ast::Stmt], id: &str) -> bool {
+    struct Refers<'a> {
+        id: &'a str,
+        found: bool,
+    }
+
+    impl<'a> Visitor for Refers<'a> {
+        fn visit_expr(&mut self, e: &syntax::ast::Expr) {
+            match e {
+                syntax::ast::Expr::Ident { id } if id == self.id => self.found = true,
+                _ => walk_expr(self, e),
+            }
+        }
     }
+
+    let mut refers = Refers { id, found: false };
+    for stmt in stmts {
+        refers.visit_stmt(stmt);
+    }
+    refers.found
+}
+
+/// Whether any of some statements refers to a variant of an enum.
+fn uses_enum(stmts: &[syntax::ast::Stmt], name: &str) -> bool {
+    struct Uses<'a> {
+        name: &'a str,
+        found: bool,
+    }
+
+    impl<'a> Visitor for Uses<'a> {
+        fn visit_expr(&mut self, e: &syntax::ast::Expr) {
+            match e {
+                syntax::ast::Expr::Variant { ty, .. } if ty == self.name => self.found = true,
+                _ => walk_expr(self, e),
+            }
+        }
+
+        fn visit_pat(&mut self, p: &syntax::ast::Pat) {
+            if let syntax::ast::Pat::Variant { ty, .. } = p {
+                self.found |= ty == self.name;
+            }
+        }
+    }
+
+    let mut uses = Uses { name, found: false };
+    for stmt in stmts {
+        uses.visit_stmt(stmt);
+    }
+    uses.found
+}
+
+/// Declares a `localparam` for every variant of an enum, so the encoded
+/// values keep their names in simulation.
+fn transform_enum(e: &syntax::ast::Enum) -> Vec<Stmt> {
+    let width = e.width();
+    e.variants
+        .iter()
+        .map(|variant| Stmt::LocalParam {
+            id: variant_name(&e.name, variant),
+            signed: false,
+            width: Some(Expr::Int { val: width }),
+            ex: Expr::Litrl {
+                val: e.value(variant).expect("variant should be declared"),
+                width,
+                signed: false,
+            },
+        })
+        .collect()
+}
+
+fn variant_name(ty: &str, variant: &str) -> String {
+    format!("{}_{}", ty, variant)
 }
 
-fn transform_port(p: &syntax::ast::Port) -> Port {
-    Port {
-        dir: transform_dir(&p.dir),
+fn transform_param(cx: &Context, p: &syntax::ast::Param) -> Param {
+    Param {
         name: p.name.clone(),
-        ty: transform_type(&p.ty),
+        default: transform_const(cx, &p.default),
+    }
+}
+
+/// Lowers a port, flattening structs and interfaces into one port per field.
+/// Every field keeps the attributes of the port.
+fn transform_port(cx: &Context, p: &syntax::ast::Port) -> Vec<Port> {
+    let attrs = transform_attrs(&p.attrs);
+    flatten_port(cx, &p.name, &p.dir, &p.ty)
+        .into_iter()
+        .map(|(name, dir, ty)| Port {
+            dir: transform_dir(&dir),
+            name,
+            ty: transform_type(cx, &ty),
+            attrs: attrs.clone(),
+        })
+        .collect()
+}
+
+/// Lowers the attributes synthesis tools understand, dropping any others.
+fn transform_attrs(attrs: &[syntax::ast::Attr]) -> Vec<Attr> {
+    attrs
+        .iter()
+        .filter(|a| a.is_synthesis())
+        .map(|a| Attr {
+            name: a.name.clone(),
+            value: a.value.clone(),
+        })
+        .collect()
+}
+
+/// Gives attributes to the declarations among some lowered statements.
+fn attributed(attrs: &[syntax::ast::Attr], stmts: Vec<Stmt>) -> Vec<Stmt> {
+    let attrs = transform_attrs(attrs);
+    if attrs.is_empty() {
+        return stmts;
+    }
+    stmts
+        .into_iter()
+        .map(|stmt| match stmt {
+            Stmt::Declare { .. } | Stmt::DeclareAssign { .. } | Stmt::Memory { .. } => {
+                Stmt::Attributed {
+                    attrs: attrs.clone(),
+                    stmt: Box::new(stmt),
+                }
+            }
+            _ => stmt,
+        })
+        .collect()
+}
+
+/// Splits a signal into the signals holding each of its fields, named after
+/// their path from it. Signals that aren't structs or interfaces stand for
+/// themselves.
+fn flatten(cx: &Context, id: &str, ty: &syntax::ast::Type) -> Vec<(String, syntax::ast::Type)> {
+    flatten_port(cx, id, &syntax::ast::Dir::Output, ty)
+        .into_iter()
+        .map(|(id, _, ty)| (id, ty))
+        .collect()
+}
+
+/// Splits a port like [`flatten`], along with the direction of every field.
+fn flatten_port(
+    cx: &Context,
+    id: &str,
+    dir: &syntax::ast::Dir,
+    ty: &syntax::ast::Type,
+) -> Vec<(String, syntax::ast::Dir, syntax::ast::Type)> {
+    let fields: Vec<(&String, syntax::ast::Dir, &syntax::ast::Type)> = match ty {
+        syntax::ast::Type::Struct { fields, .. } => fields
+            .iter()
+            .map(|f| (&f.name, dir.clone(), &f.ty))
+            .collect(),
+        syntax::ast::Type::Interface { fields, .. } => fields
+            .iter()
+            .map(|f| (&f.name, dir.compose(&f.dir), &f.ty))
+            .collect(),
+        syntax::ast::Type::Flipped { ty } => return flatten_port(cx, id, &dir.flipped(), ty),
+        syntax::ast::Type::Alias { ty, .. } => return flatten_port(cx, id, dir, ty),
+        // Types named in declarations are expanded as they were declared.
+        syntax::ast::Type::Named { name } => {
+            if let Some(s) = cx.ast.structure(name) {
+                s.fields
+                    .iter()
+                    .map(|f| (&f.name, dir.clone(), &f.ty))
+                    .collect()
+            } else if let Some(i) = cx.ast.interface(name) {
+                i.fields
+                    .iter()
+                    .map(|f| (&f.name, dir.compose(&f.dir), &f.ty))
+                    .collect()
+            } else {
+                return vec![(id.to_string(), dir.clone(), ty.clone())];
+            }
+        }
+        _ => return vec![(id.to_string(), dir.clone(), ty.clone())],
+    };
+    fields
+        .iter()
+        .flat_map(|(name, dir, ty)| flatten_port(cx, &field_name(id, name), dir, ty))
+        .collect()
+}
+
+fn field_name(id: &str, field: &str) -> String {
+    format!("{}_{}", id, field)
+}
+
+/// The name of the flattened signal a place refers to.
+fn place_name(e: &syntax::ast::Expr) -> String {
+    match e {
+        syntax::ast::Expr::Ident { id } => id.clone(),
+        syntax::ast::Expr::Field { ex, field } => field_name(&place_name(ex), field),
+        syntax::ast::Expr::Paren { ex } | syntax::ast::Expr::Cross { ex } => place_name(ex),
+        _ => unreachable!("struct values should be checked before lowering"),
+    }
+}
+
+/// Lowers a constant into a `localparam`. Constants that depend on parameters
+/// keep their expression, while any others are replaced by their value.
+fn transform_localparam(cx: &Context, c: &syntax::ast::Const) -> Stmt {
+    let (signed, width) = match &c.ty {
+        Some(ty) => transform_shape(cx, ty),
+        None => (false, None),
+    };
+    let ex = if cx.is_generic(&c.ex) {
+        transform_const(cx, &c.ex)
+    } else {
+        let v = cx
+            .scope
+            .constant(&c.name)
+            .expect("constant should be checked before lowering");
+        match v.width {
+            Some(width) => Expr::Litrl {
+                val: v.bits(),
+                width,
+                signed,
+            },
+            None => transform_value(v),
+        }
+    };
+
+    Stmt::LocalParam {
+        id: c.name.clone(),
+        signed,
+        width,
+        ex,
     }
 }
 
@@ -26,65 +594,829 @@ fn transform_dir(d: &syntax::ast::Dir) -> Dir {
     match d {
         syntax::ast::Dir::Input => Dir::Input,
         syntax::ast::Dir::Output => Dir::Output,
+        syntax::ast::Dir::InOut => Dir::InOut,
+    }
+}
+
+fn transform_assertion(kind: &syntax::ast::Assertion) -> Assertion {
+    match kind {
+        syntax::ast::Assertion::Assert => Assertion::Assert,
+        syntax::ast::Assertion::Assume => Assertion::Assume,
+        syntax::ast::Assertion::Cover => Assertion::Cover,
     }
 }
 
-fn transform_type(t: &syntax::ast::Type) -> Type {
+fn transform_type(cx: &Context, t: &syntax::ast::Type) -> Type {
+    let (signed, width) = transform_shape(cx, t);
+    Type::Wire { signed, width }
+}
+
+fn transform_reg_type(cx: &Context, t: &syntax::ast::Type) -> Type {
+    let (signed, width) = transform_shape(cx, t);
+    Type::Reg { signed, width }
+}
+
+/// The signedness and vector width of a type, `None` for scalars.
+fn transform_shape(cx: &Context, t: &syntax::ast::Type) -> (bool, Option<Expr>) {
     match t {
-        syntax::ast::Type::Bit => Type::Wire,
+        syntax::ast::Type::Bit
+        | syntax::ast::Type::Clock
+        | syntax::ast::Type::Reset
+        | syntax::ast::Type::AsyncReset => (false, None),
+        syntax::ast::Type::Bits { width } | syntax::ast::Type::UInt { width } => {
+            (false, Some(Expr::Int { val: *width }))
+        }
+        syntax::ast::Type::SInt { width } => (true, Some(Expr::Int { val: *width })),
+        syntax::ast::Type::Generic { kind, width, .. } => (
+            *kind == syntax::ast::Vector::SInt,
+            Some(transform_const(cx, width)),
+        ),
+        syntax::ast::Type::Named { name } => {
+            let e = cx
+                .ast
+                .enumeration(name)
+                .expect("type should be checked before lowering");
+            (false, Some(Expr::Int { val: e.width() }))
+        }
+        syntax::ast::Type::Enum { width, .. } => (false, Some(Expr::Int { val: *width })),
+        syntax::ast::Type::Alias { ty, .. } => transform_shape(cx, ty),
+        syntax::ast::Type::Struct { .. }
+        | syntax::ast::Type::Interface { .. }
+        | syntax::ast::Type::Flipped { .. } => unreachable!("aggregates should be flattened"),
+        syntax::ast::Type::Array { .. } | syntax::ast::Type::GenericArray { .. } => {
+            unreachable!("arrays should only type memories")
+        }
     }
 }
 
-fn transform_stmt(s: &syntax::ast::Stmt) -> Stmt {
-    match s {
-        syntax::ast::Stmt::Assign { id, ex } => Stmt::Assign {
-            id: id.clone(),
-            ex: transform_expr(ex),
+/// Lowers an expression over literals, constants and parameters, such as a
+/// width or a parameter value, keeping any parameters symbolic. Constants are
+/// substituted, since these expressions may appear ahead of their declaration.
+fn transform_const(cx: &Context, e: &syntax::ast::Expr) -> Expr {
+    if !cx.is_generic(e) {
+        if let Some(v) = cx.scope.eval(e) {
+            return transform_value(&v);
+        }
+    }
+
+    match e {
+        syntax::ast::Expr::Binary { lex, op, rex, .. } => {
+            let op = transform_binary_op(op, &syntax::ast::Type::Bit);
+            Expr::Binary {
+                lex: Box::new(group(transform_const(cx, lex), &op, false)),
+                rex: Box::new(group(transform_const(cx, rex), &op, true)),
+                op,
+            }
+        }
+        syntax::ast::Expr::Unary { op, ex } => Expr::Unary {
+            op: transform_unary_op(op),
+            ex: Box::new(group_ternary(transform_const(cx, ex))),
         },
-        syntax::ast::Stmt::Declare { id, ty } => Stmt::Declare {
-            id: id.clone(),
-            ty: transform_type(ty),
+        syntax::ast::Expr::Paren { ex } => Expr::Paren {
+            ex: Box::new(transform_const(cx, ex)),
         },
-        syntax::ast::Stmt::DeclareAssign { id, ty, ex } => Stmt::DeclareAssign {
-            id: id.clone(),
-            ty: transform_type(ty),
-            ex: transform_expr(ex),
+        syntax::ast::Expr::If { cond, tex, fex } => Expr::Ternary {
+            cond: Box::new(group_ternary(transform_const(cx, cond))),
+            tex: Box::new(transform_const(cx, tex)),
+            fex: Box::new(transform_const(cx, fex)),
+        },
+        syntax::ast::Expr::Index { ex, idx } => Expr::Index {
+            ex: Box::new(transform_const(cx, ex)),
+            idx: Box::new(transform_const(cx, idx)),
+        },
+        syntax::ast::Expr::Slice { ex, msb, lsb } => Expr::Slice {
+            ex: Box::new(transform_const(cx, ex)),
+            msb: Box::new(transform_const(cx, msb)),
+            lsb: Box::new(transform_const(cx, lsb)),
+        },
+        syntax::ast::Expr::Concat { exs } => Expr::Concat {
+            exs: exs.iter().map(|ex| transform_const(cx, ex)).collect(),
         },
+        syntax::ast::Expr::Repeat { count, ex } => Expr::Repeat {
+            count: Box::new(transform_const(cx, count)),
+            ex: Box::new(transform_const(cx, ex)),
+        },
+        syntax::ast::Expr::Ident { id } => match cx.consts.get(id.as_str()) {
+            Some(c) => Expr::Paren {
+                ex: Box::new(transform_const(cx, &c.ex)),
+            },
+            None => Expr::Ident { id: id.clone() },
+        },
+        syntax::ast::Expr::Litrl { val, .. } => match val.to_usize() {
+            Some(val) => Expr::Int { val },
+            None => Expr::Litrl {
+                val: val.clone(),
+                width: val.bits(),
+                signed: false,
+            },
+        },
+        _ => unreachable!("constant expressions should be checked before lowering"),
     }
 }
 
-fn transform_expr(e: &syntax::ast::Expr) -> Expr {
+/// Lowers the value of a constant into an integer, or a literal where it is
+/// too large for one.
+fn transform_value(v: &Value) -> Expr {
+    let int = |val: &BigInt| match val.to_usize() {
+        Some(val) => Expr::Int { val },
+        None => {
+            let val = val.to_biguint().unwrap();
+            Expr::Litrl {
+                width: val.bits(),
+                val,
+                signed: false,
+            }
+        }
+    };
+    match v.val.sign() {
+        Sign::Minus => Expr::Paren {
+            ex: Box::new(Expr::Binary {
+                lex: Box::new(Expr::Int { val: 0 }),
+                op: BinaryOp::Sub,
+                rex: Box::new(int(&-&v.val)),
+            }),
+        },
+        _ => int(&v.val),
+    }
+}
+
+fn transform_stmt(cx: &mut Context, s: &syntax::ast::Stmt) -> Vec<Stmt> {
+    match s {
+        syntax::ast::Stmt::Assert {
+            kind, ex, clk, rst, ..
+        } => {
+            let kind = transform_assertion(kind);
+            let ex = transform_expr(cx, ex, &syntax::ast::Type::Bit);
+            match clk {
+                Some(clk) => vec![Stmt::Property {
+                    kind,
+                    clk: clk.clone(),
+                    rst: rst.clone(),
+                    ex,
+                }],
+                None => vec![Stmt::Always {
+                    event: Event::Any,
+                    body: vec![Proc::Assert { kind, ex }],
+                }],
+            }
+        }
+        syntax::ast::Stmt::Assign { id, fields, ex, .. } => {
+            let ty = cx
+                .scope
+                .lookup_path(id, fields)
+                .expect("identifier should be checked before lowering");
+            let place = fields.iter().fold(id.clone(), |id, f| field_name(&id, f));
+            let assigns = transform_assigns(cx, &place, &ty, ex);
+            match cx.scope.reg(id).cloned() {
+                Some(reg) => vec![transform_reg_assign(cx, id, &reg, assigns)],
+                None => assigns
+                    .into_iter()
+                    .map(|(id, ex)| Stmt::Assign { id, ex })
+                    .collect(),
+            }
+        }
+        syntax::ast::Stmt::Declare { id, ty, attrs, .. } => {
+            let decls = flatten(cx, id, ty)
+                .into_iter()
+                .map(|(id, ty)| Stmt::Declare {
+                    id,
+                    ty: transform_type(cx, &ty),
+                })
+                .collect();
+            attributed(attrs, decls)
+        }
+        syntax::ast::Stmt::DeclareAssign {
+            id, ty, ex, attrs, ..
+        } => {
+            let assigns = transform_assigns(cx, id, &lookup(&cx.scope, id), ex);
+            let decls = flatten(cx, id, ty)
+                .into_iter()
+                .zip(assigns)
+                .map(|((id, ty), (_, ex))| Stmt::DeclareAssign {
+                    id,
+                    ty: transform_type(cx, &ty),
+                    ex,
+                })
+                .collect();
+            attributed(attrs, decls)
+        }
+        syntax::ast::Stmt::Reg {
+            id,
+            ty,
+            rst,
+            init,
+            attrs,
+            ..
+        } => {
+            let decls = match init {
+                // registers without a reset start out at their initial value
+                Some(init) if rst.is_none() => vec![Stmt::DeclareAssign {
+                    id: id.clone(),
+                    ty: transform_reg_type(cx, ty),
+                    ex: transform_expr(cx, init, &lookup(&cx.scope, id)),
+                }],
+                _ => flatten(cx, id, ty)
+                    .into_iter()
+                    .map(|(id, ty)| Stmt::Declare {
+                        id,
+                        ty: transform_reg_type(cx, &ty),
+                    })
+                    .collect(),
+            };
+            let mut stmts = attributed(attrs, decls);
+
+            // A register that is only read still loads its reset value,
+            // holding it from then on.
+            let reg = cx
+                .scope
+                .reg(id)
+                .cloned()
+                .expect("register should be checked before lowering");
+            if reg.rst.is_some() && reg.init.is_some() && !cx.assigned.contains(id) {
+                stmts.push(transform_reg_assign(cx, id, &reg, vec![]));
+            }
+            stmts
+        }
+        syntax::ast::Stmt::Inst {
+            id,
+            module,
+            params,
+            conns,
+            ..
+        } => {
+            let m = cx
+                .ast
+                .module(module)
+                .expect("module should be checked before lowering");
+            let m =
+                resolve_defaults(cx.ast, m).expect("parameters should be checked before lowering");
+            let conns = conns
+                .iter()
+                .flat_map(|conn| {
+                    let port = m
+                        .ports
+                        .iter()
+                        .find(|p| p.name == conn.port)
+                        .expect("port should be checked before lowering");
+                    transform_assigns(cx, &conn.port, &port.ty, &conn.ex)
+                        .into_iter()
+                        .map(|(port, ex)| Conn { port, ex })
+                })
+                .collect();
+            vec![Stmt::Inst {
+                id: id.clone(),
+                module: module.clone(),
+                params: params
+                    .iter()
+                    .map(|param| Conn {
+                        port: param.port.clone(),
+                        ex: transform_const(cx, &param.ex),
+                    })
+                    .collect(),
+                conns,
+            }]
+        }
+        syntax::ast::Stmt::Mem {
+            id,
+            ty,
+            init,
+            attrs,
+            ..
+        } => {
+            let decls = transform_mem(cx, id, ty, init);
+            attributed(attrs, decls)
+        }
+        syntax::ast::Stmt::MemWrite {
+            id, addr, ex, en, ..
+        } => {
+            let (elem, _) = cx
+                .scope
+                .memory_of(&syntax::ast::Expr::Ident { id: id.clone() })
+                .expect("memory should be checked before lowering");
+            let elem = elem.clone();
+            let clk = memory_clock(cx, id);
+            let write = Proc::IndexNonBlockingAssign {
+                id: id.clone(),
+                idx: transform_index(cx, addr),
+                ex: transform_expr(cx, ex, &elem),
+            };
+            let body = match en {
+                Some(en) => vec![Proc::If {
+                    cond: transform_expr(cx, en, &syntax::ast::Type::Bit),
+                    tstmts: vec![write],
+                    fstmts: vec![],
+                }],
+                None => vec![write],
+            };
+            vec![Stmt::Always {
+                event: Event::Posedge { ids: vec![clk] },
+                body,
+            }]
+        }
+        syntax::ast::Stmt::Const(c) if c.ty.as_ref().is_some_and(|ty| ty.is_array()) => vec![],
+        syntax::ast::Stmt::Const(c) => vec![transform_localparam(cx, c)],
+        syntax::ast::Stmt::For { .. } | syntax::ast::Stmt::If { .. } => {
+            unreachable!("generation blocks should be expanded before lowering")
+        }
+    }
+}
+
+/// Lowers a memory into an unpacked array of registers, along with an
+/// `initial` block loading its contents if it has any.
+fn transform_mem(
+    cx: &mut Context,
+    id: &str,
+    ty: &syntax::ast::Type,
+    init: &Option<syntax::ast::MemInit>,
+) -> Vec<Stmt> {
+    let (elem, last) = match ty.unaliased() {
+        syntax::ast::Type::Array { elem, len } => (elem, Expr::Int { val: len - 1 }),
+        syntax::ast::Type::GenericArray { elem, len, .. } => {
+            let last = syntax::ast::Expr::Binary {
+                lex: len.clone(),
+                op: syntax::ast::BinaryOp::Sub,
+                rex: Box::new(syntax::ast::Expr::Litrl {
+                    val: 1u8.into(),
+                    width: None,
+                }),
+                pos: 0,
+            };
+            (elem, transform_const(cx, &last))
+        }
+        _ => unreachable!("memories should be checked before lowering"),
+    };
+    let mut stmts = vec![Stmt::Memory {
+        id: id.to_string(),
+        ty: transform_reg_type(cx, elem),
+        last,
+    }];
+
+    let body = match init {
+        Some(syntax::ast::MemInit::File(file)) => vec![Proc::ReadMemH {
+            file: file.clone(),
+            id: id.to_string(),
+        }],
+        Some(syntax::ast::MemInit::Const(ex)) => {
+            let ty = lookup(&cx.scope, id);
+            let (elem, len) = match ty.unaliased() {
+                syntax::ast::Type::Array { elem, len } => (elem, *len),
+                _ => unreachable!("memories should be checked before lowering"),
+            };
+            cx.scope
+                .eval_array(ex, elem, len)
+                .expect("memory contents should be checked before lowering")
+                .iter()
+                .enumerate()
+                .map(|(idx, v)| Proc::IndexAssign {
+                    id: id.to_string(),
+                    idx: Expr::Int { val: idx },
+                    ex: Expr::Litrl {
+                        val: v.bits(),
+                        width: elem.width(),
+                        signed: elem.is_signed(),
+                    },
+                })
+                .collect()
+        }
+        None => return stmts,
+    };
+    stmts.push(Stmt::Initial { body });
+    stmts
+}
+
+/// Lowers a read from a memory into a register loaded on the memory's clock,
+/// so the read is synchronous and maps onto block RAM.
+fn transform_mem_read(cx: &mut Context, e: &syntax::ast::Expr, idx: &syntax::ast::Expr) -> Expr {
+    let mem = match e {
+        syntax::ast::Expr::Index { ex, .. } => place_name(ex),
+        _ => unreachable!(),
+    };
+    let ty = type_of(&cx.scope, e);
+    let clk = memory_clock(cx, &mem);
+    let id = cx.temp(&format!("{}_rdata", mem));
+    let addr = transform_index(cx, idx);
+
+    cx.hoisted.push(Stmt::Declare {
+        id: id.clone(),
+        ty: transform_reg_type(cx, &ty),
+    });
+    cx.hoisted.push(Stmt::Always {
+        event: Event::Posedge { ids: vec![clk] },
+        body: vec![Proc::NonBlockingAssign {
+            id: id.clone(),
+            ex: Expr::Index {
+                ex: Box::new(Expr::Ident { id: mem }),
+                idx: Box::new(addr),
+            },
+        }],
+    });
+
+    Expr::Ident { id }
+}
+
+fn memory_clock(cx: &Context, id: &str) -> String {
+    cx.scope
+        .memory(id)
+        .expect("memory should be checked before lowering")
+        .clk
+        .clone()
+}
+
+/// Lowers an expression assigned to `id` of type `ty`, pairing every signal
+/// `id` flattens into with the expression it is assigned.
+fn transform_assigns(
+    cx: &mut Context,
+    id: &str,
+    ty: &syntax::ast::Type,
+    ex: &syntax::ast::Expr,
+) -> Vec<(String, Expr)> {
+    if !ty.is_struct() && !ty.is_interface() {
+        return vec![(id.to_string(), transform_expr(cx, ex, ty))];
+    }
+
+    let from = flatten(cx, &place_name(ex), ty);
+    flatten(cx, id, ty)
+        .into_iter()
+        .zip(from)
+        .map(|((id, _), (from, _))| (id, Expr::Ident { id: from }))
+        .collect()
+}
+
+/// Lowers the next value of a register into a clocked `always` block, which
+/// loads the reset value instead while its reset is asserted.
+fn transform_reg_assign(
+    cx: &mut Context,
+    id: &str,
+    reg: &Reg,
+    assigns: Vec<(String, Expr)>,
+) -> Stmt {
+    let next = assigns
+        .into_iter()
+        .map(|(id, ex)| Proc::NonBlockingAssign { id, ex })
+        .collect();
+
+    let body = match (&reg.rst, &reg.init) {
+        (Some(rst), Some(init)) => vec![Proc::If {
+            cond: Expr::Ident { id: rst.clone() },
+            tstmts: vec![Proc::NonBlockingAssign {
+                id: id.to_string(),
+                ex: transform_expr(cx, init, &lookup(&cx.scope, id)),
+            }],
+            fstmts: next,
+        }],
+        _ => next,
+    };
+
+    // Asynchronous resets also need to wake the block up.
+    let mut ids = vec![reg.clk.clone()];
+    match &reg.rst {
+        Some(rst) if lookup(&cx.scope, rst) == syntax::ast::Type::AsyncReset => {
+            ids.push(rst.clone());
+        }
+        _ => {}
+    }
+
+    Stmt::Always {
+        event: Event::Posedge { ids },
+        body,
+    }
+}
+
+/// Lowers an expression evaluated in the context of type `ctx`, which
+/// determines the size and signedness of any unsized literals within it.
+fn transform_expr(cx: &mut Context, e: &syntax::ast::Expr, ctx: &syntax::ast::Type) -> Expr {
     match e {
-        syntax::ast::Expr::Binary { lex, op, rex } => Expr::Binary {
-            lex: Box::new(transform_expr(lex)),
-            op: transform_binary_op(op),
-            rex: Box::new(transform_expr(rex)),
+        syntax::ast::Expr::Binary { lex, op, rex, .. } => {
+            let (lctx, rctx) = match op {
+                syntax::ast::BinaryOp::ShiftLeft | syntax::ast::BinaryOp::ShiftRight => {
+                    (ctx.clone(), type_of(&cx.scope, rex))
+                }
+                syntax::ast::BinaryOp::Equal
+                | syntax::ast::BinaryOp::NotEqual
+                | syntax::ast::BinaryOp::Less
+                | syntax::ast::BinaryOp::LessEqual
+                | syntax::ast::BinaryOp::Greater
+                | syntax::ast::BinaryOp::GreaterEqual => {
+                    let ty = cx
+                        .scope
+                        .operand_type(lex, rex)
+                        .expect("expression should be checked before lowering");
+                    (ty.clone(), ty)
+                }
+                syntax::ast::BinaryOp::LogicAnd | syntax::ast::BinaryOp::LogicOr => {
+                    (type_of(&cx.scope, lex), type_of(&cx.scope, rex))
+                }
+                _ => (ctx.clone(), ctx.clone()),
+            };
+            let vop = transform_binary_op(op, &type_of(&cx.scope, lex));
+            let (lex, rex) = match op {
+                // the operands of a comparison are only as wide as the wider
+                // of them in Verilog
+                syntax::ast::BinaryOp::Equal
+                | syntax::ast::BinaryOp::NotEqual
+                | syntax::ast::BinaryOp::Less
+                | syntax::ast::BinaryOp::LessEqual
+                | syntax::ast::BinaryOp::Greater
+                | syntax::ast::BinaryOp::GreaterEqual => (
+                    transform_operand(cx, lex, &lctx),
+                    transform_operand(cx, rex, &rctx),
+                ),
+                // bits shifted down from a carry must be there to begin with
+                syntax::ast::BinaryOp::ShiftLeft | syntax::ast::BinaryOp::ShiftRight => (
+                    transform_operand(cx, lex, &lctx),
+                    transform_expr(cx, rex, &rctx),
+                ),
+                _ => (
+                    transform_expr(cx, lex, &lctx),
+                    transform_expr(cx, rex, &rctx),
+                ),
+            };
+            Expr::Binary {
+                lex: Box::new(group(lex, &vop, false)),
+                rex: Box::new(group(rex, &vop, true)),
+                op: vop,
+            }
+        }
+        syntax::ast::Expr::Unary { op, ex } => {
+            let ctx = match op {
+                syntax::ast::UnaryOp::Negate => ctx.clone(),
+                _ => type_of(&cx.scope, ex),
+            };
+            let ex = match op {
+                syntax::ast::UnaryOp::Negate => transform_expr(cx, ex, &ctx),
+                _ => transform_operand(cx, ex, &ctx),
+            };
+            Expr::Unary {
+                op: transform_unary_op(op),
+                ex: Box::new(group_ternary(ex)),
+            }
+        }
+        syntax::ast::Expr::Paren { ex } => Expr::Paren {
+            ex: Box::new(transform_expr(cx, ex, ctx)),
         },
-        syntax::ast::Expr::Unary { op, ex } => Expr::Unary {
-            op: transform_unary_op(op),
-            ex: Box::new(transform_expr(ex)),
+        syntax::ast::Expr::If { cond, tex, fex } => {
+            let cond = transform_expr(cx, cond, &type_of(&cx.scope, cond));
+            Expr::Ternary {
+                cond: Box::new(group_ternary(cond)),
+                tex: Box::new(transform_expr(cx, tex, ctx)),
+                fex: Box::new(transform_expr(cx, fex, ctx)),
+            }
+        }
+        syntax::ast::Expr::Match { ex, arms } => transform_match(cx, e, ex, arms),
+        syntax::ast::Expr::Index { idx, .. } if cx.scope.memory_of(e).is_some() => {
+            transform_mem_read(cx, e, idx)
+        }
+        syntax::ast::Expr::Index { ex, idx } => Expr::Index {
+            ex: Box::new(transform_expr(cx, ex, ctx)),
+            idx: Box::new(transform_index(cx, idx)),
         },
-        syntax::ast::Expr::Paren { ex } => Expr::Paren {
-            ex: Box::new(transform_expr(ex)),
+        syntax::ast::Expr::Slice { ex, msb, lsb } => Expr::Slice {
+            ex: Box::new(transform_expr(cx, ex, ctx)),
+            msb: Box::new(transform_index(cx, msb)),
+            lsb: Box::new(transform_index(cx, lsb)),
+        },
+        syntax::ast::Expr::Concat { exs } => Expr::Concat {
+            exs: exs
+                .iter()
+                .map(|ex| transform_operand(cx, ex, &type_of(&cx.scope, ex)))
+                .collect(),
+        },
+        syntax::ast::Expr::Repeat { count, ex } => Expr::Repeat {
+            count: Box::new(transform_index(cx, count)),
+            ex: Box::new(transform_operand(cx, ex, &type_of(&cx.scope, ex))),
+        },
+        syntax::ast::Expr::Tristate { en, ex } => Expr::Ternary {
+            cond: Box::new(group_ternary(transform_expr(
+                cx,
+                en,
+                &syntax::ast::Type::Bit,
+            ))),
+            tex: Box::new(transform_expr(cx, ex, ctx)),
+            fex: Box::new(Expr::HighZ {
+                width: ctx.width().max(1),
+            }),
+        },
+        syntax::ast::Expr::Cross { ex } => transform_expr(cx, ex, ctx),
+        syntax::ast::Expr::Array { .. } => unreachable!("arrays should only initialize memories"),
+        syntax::ast::Expr::Call { .. } => unreachable!("calls should be inlined before lowering"),
+        syntax::ast::Expr::Field { .. } => Expr::Ident { id: place_name(e) },
+        syntax::ast::Expr::Variant { ty, variant } => Expr::Ident {
+            id: variant_name(ty, variant),
+        },
+        syntax::ast::Expr::Ident { id } => match cx.scope.constant(id) {
+            // untyped constants stand in for unsized literals
+            Some(v) if v.width.is_none() && !cx.is_generic(e) => {
+                transform_expr(cx, &to_expr(v), ctx)
+            }
+            _ => Expr::Ident { id: id.clone() },
+        },
+        syntax::ast::Expr::Litrl { val, width } => Expr::Litrl {
+            val: val.clone(),
+            width: width.unwrap_or_else(|| ctx.width().max(val.bits()).max(1)),
+            signed: width.is_none() && ctx.is_signed(),
+        },
+    }
+}
+
+/// Lowers an operand that Verilog sizes on its own, or only against the other
+/// operand of a comparison, rather than by the context Silica types it in.
+/// Arithmetic within such an operand would lose its carry, so the operand is
+/// first computed into a wire of its full width.
+fn transform_operand(cx: &mut Context, e: &syntax::ast::Expr, ctx: &syntax::ast::Type) -> Expr {
+    if !has_carry(e) {
+        return transform_expr(cx, e, ctx);
+    }
+
+    let mut e = e;
+    while let syntax::ast::Expr::Paren { ex } = e {
+        e = ex;
+    }
+    let ty = type_of(&cx.scope, e);
+    let ex = transform_expr(cx, e, &ty);
+    let id = cx.temp("wide");
+    cx.hoisted.push(Stmt::DeclareAssign {
+        id: id.clone(),
+        ty: transform_type(cx, &ty),
+        ex,
+    });
+    Expr::Ident { id }
+}
+
+/// Whether an expression is wider than its operands because of arithmetic,
+/// which Verilog only computes at the width of the surrounding expression.
+fn has_carry(e: &syntax::ast::Expr) -> bool {
+    match e {
+        syntax::ast::Expr::Binary { lex, op, rex, .. } => match op {
+            syntax::ast::BinaryOp::Add
+            | syntax::ast::BinaryOp::Sub
+            | syntax::ast::BinaryOp::Mul => true,
+            syntax::ast::BinaryOp::BitAnd
+            | syntax::ast::BinaryOp::BitXor
+            | syntax::ast::BinaryOp::BitOr => has_carry(lex) || has_carry(rex),
+            syntax::ast::BinaryOp::ShiftLeft | syntax::ast::BinaryOp::ShiftRight => has_carry(lex),
+            _ => false,
         },
-        syntax::ast::Expr::Ident { id } => Expr::Ident { id: id.clone() },
-        syntax::ast::Expr::Litrl { val } => Expr::Litrl { val: val.clone() },
+        syntax::ast::Expr::Unary {
+            op: syntax::ast::UnaryOp::Negate,
+            ex,
+        }
+        | syntax::ast::Expr::Paren { ex }
+        | syntax::ast::Expr::Cross { ex } => has_carry(ex),
+        syntax::ast::Expr::If { tex, fex, .. } => has_carry(tex) || has_carry(fex),
+        _ => false,
+    }
+}
+
+/// Parenthesizes an operand whose operator binds more loosely in Verilog than
+/// the operator it is applied to, since Silica and Verilog disagree on where
+/// comparisons and conditionals sit relative to the binary operators.
+fn group(ex: Expr, op: &BinaryOp, right: bool) -> Expr {
+    let inner = match &ex {
+        Expr::Binary { op, .. } => op.precedence(),
+        Expr::Ternary { .. } => 0,
+        _ => return ex,
+    };
+
+    if inner < op.precedence() || (right && inner == op.precedence()) {
+        Expr::Paren { ex: Box::new(ex) }
+    } else {
+        ex
+    }
+}
+
+/// Parenthesizes a conditional used where a tighter binding operand is needed.
+fn group_ternary(ex: Expr) -> Expr {
+    match ex {
+        Expr::Ternary { .. } => Expr::Paren { ex: Box::new(ex) },
+        _ => ex,
+    }
+}
+
+/// Constant indices and repeat counts are emitted as plain integers rather
+/// than sized literals, or in terms of the parameters they depend on.
+fn transform_index(cx: &mut Context, e: &syntax::ast::Expr) -> Expr {
+    match cx.scope.const_index(e) {
+        Some(_) if cx.is_generic(e) => transform_const(cx, e),
+        Some(val) => Expr::Int { val },
+        None => {
+            let ty = type_of(&cx.scope, e);
+            transform_expr(cx, e, &ty)
+        }
     }
 }
 
-fn transform_binary_op(b: &syntax::ast::BinaryOp) -> BinaryOp {
+/// Lowers a match into a `case` statement within a combinational `always`
+/// block, driving a temporary register that stands in for the expression.
+/// Every `case` gets a default, so no latches are inferred.
+fn transform_match(
+    cx: &mut Context,
+    e: &syntax::ast::Expr,
+    ex: &syntax::ast::Expr,
+    arms: &[syntax::ast::Arm],
+) -> Expr {
+    let ty = type_of(&cx.scope, e);
+    let ex_ty = type_of(&cx.scope, ex);
+    let id = cx.temp("match");
+    let ex = transform_expr(cx, ex, &ex_ty);
+
+    let mut items = Vec::new();
+    let mut default = None;
+    for arm in arms {
+        let body = Proc::Assign {
+            id: id.clone(),
+            ex: transform_expr(cx, &arm.ex, &ty),
+        };
+        if arm.pats.contains(&syntax::ast::Pat::Wildcard) {
+            default = Some(body);
+            break;
+        }
+        let labels = arm
+            .pats
+            .iter()
+            .map(|pat| match pat {
+                syntax::ast::Pat::Litrl { val, .. } => Expr::Litrl {
+                    val: val.clone(),
+                    width: ex_ty.width(),
+                    signed: false,
+                },
+                syntax::ast::Pat::Variant { ty, variant } => Expr::Ident {
+                    id: variant_name(ty, variant),
+                },
+                syntax::ast::Pat::Wildcard => unreachable!(),
+            })
+            .collect();
+        items.push(CaseItem { labels, body });
+    }
+
+    // an exhaustive match without a wildcard covers its last arm by default
+    let default = match default {
+        Some(default) => default,
+        None => {
+            items
+                .pop()
+                .expect("match should be checked before lowering")
+                .body
+        }
+    };
+
+    cx.hoisted.push(Stmt::Declare {
+        id: id.clone(),
+        ty: transform_reg_type(cx, &ty),
+    });
+    cx.hoisted.push(Stmt::Always {
+        event: Event::Any,
+        body: vec![Proc::Case {
+            ex,
+            items,
+            default: Box::new(default),
+        }],
+    });
+
+    Expr::Ident { id }
+}
+
+fn lookup(scope: &Scope, id: &str) -> syntax::ast::Type {
+    scope
+        .lookup(id)
+        .cloned()
+        .expect("identifier should be checked before lowering")
+}
+
+fn type_of(scope: &Scope, e: &syntax::ast::Expr) -> syntax::ast::Type {
+    scope
+        .type_of(e)
+        .expect("expression should be checked before lowering")
+}
+
+/// Lowers a binary operator, given the type of its left operand.
+fn transform_binary_op(b: &syntax::ast::BinaryOp, lty: &syntax::ast::Type) -> BinaryOp {
     match b {
         syntax::ast::BinaryOp::ShiftLeft => BinaryOp::ShiftLeft,
+        syntax::ast::BinaryOp::ShiftRight if lty.is_signed() => BinaryOp::ShiftRightArith,
         syntax::ast::BinaryOp::ShiftRight => BinaryOp::ShiftRight,
         syntax::ast::BinaryOp::BitAnd => BinaryOp::BitAnd,
         syntax::ast::BinaryOp::BitXor => BinaryOp::BitXor,
         syntax::ast::BinaryOp::BitOr => BinaryOp::BitOr,
+        syntax::ast::BinaryOp::Add => BinaryOp::Add,
+        syntax::ast::BinaryOp::Sub => BinaryOp::Sub,
+        syntax::ast::BinaryOp::Mul => BinaryOp::Mul,
+        syntax::ast::BinaryOp::Div => BinaryOp::Div,
+        syntax::ast::BinaryOp::Rem => BinaryOp::Rem,
+        syntax::ast::BinaryOp::Equal => BinaryOp::Equal,
+        syntax::ast::BinaryOp::NotEqual => BinaryOp::NotEqual,
+        syntax::ast::BinaryOp::Less => BinaryOp::Less,
+        syntax::ast::BinaryOp::LessEqual => BinaryOp::LessEqual,
+        syntax::ast::BinaryOp::Greater => BinaryOp::Greater,
+        syntax::ast::BinaryOp::GreaterEqual => BinaryOp::GreaterEqual,
+        syntax::ast::BinaryOp::LogicAnd => BinaryOp::LogicAnd,
+        syntax::ast::BinaryOp::LogicOr => BinaryOp::LogicOr,
     }
 }
 
 fn transform_unary_op(u: &syntax::ast::UnaryOp) -> UnaryOp {
     match u {
         syntax::ast::UnaryOp::Negate => UnaryOp::Negate,
+        syntax::ast::UnaryOp::LogicNot => UnaryOp::LogicNot,
         syntax::ast::UnaryOp::ReductAnd => UnaryOp::ReductAnd,
         syntax::ast::UnaryOp::ReductXor => UnaryOp::ReductXor,
         syntax::ast::UnaryOp::ReductOr => UnaryOp::ReductOr,
@@ -1,6 +1,10 @@
+use std::fmt::Debug;
 use std::fs::File;
-use std::io::Read;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use syntax::source::SourceMap;
+use syntax::BytePos;
 
 fn main() {
     let matches = clap::App::new("silicac")
@@ -22,19 +26,56 @@ fn main() {
                 .required(true)
                 .help("The output file to generate"),
         )
+        .arg(
+            clap::Arg::with_name("search-path")
+                .short("L")
+                .long("search-path")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("DIR")
+                .help("Adds a directory to look for used files in, after that of the input file"),
+        )
+        .arg(
+            clap::Arg::with_name("monomorphize")
+                .long("monomorphize")
+                .help("Generates a module for every set of parameter values instead of parameters"),
+        )
         .get_matches();
 
     let input_filename = matches
         .value_of("input")
         .expect("Failed to get input file path");
-    let mut input_file = File::open(input_filename).expect("Input file not found");
-    let mut input = String::new();
-    input_file
-        .read_to_string(&mut input)
-        .expect("Failed to read file");
+    let input_path = Path::new(input_filename);
+    let mut search = vec![input_path
+        .parent()
+        .map_or_else(PathBuf::new, Path::to_path_buf)];
+    search.extend(
+        matches
+            .values_of("search-path")
+            .into_iter()
+            .flatten()
+            .map(PathBuf::from),
+    );
 
-    let ast = syntax::parse_source(&input).expect("Failed to parse the source");
-    let verilog = verilog::Verilog::new(&ast).build();
+    let mut sources = SourceMap::new();
+    let ast = syntax::source::load(input_path, &search, &mut sources)
+        .unwrap_or_else(|e| fail("Failed to load the source", e.pos(), None, &e, &sources));
+    if let Err(e) = syntax::check::check_ast(&ast) {
+        fail(
+            "Failed to check the source",
+            e.pos(),
+            e.prev(),
+            &e,
+            &sources,
+        );
+    }
+    for w in syntax::check::warnings(&ast) {
+        warn(w.pos(), &w, &sources);
+    }
+    let verilog = verilog::Verilog::new(&ast)
+        .monomorphize(matches.is_present("monomorphize"))
+        .build();
 
     let output_filename = matches
         .value_of("output")
@@ -44,3 +85,38 @@ fn main() {
         .write_all(verilog.as_bytes())
         .expect("Unable to write to file");
 }
+
+/// Reports an error, along with the file, line and column it points to and
+/// those of any earlier definition it clashes with.
+fn fail(
+    msg: &str,
+    pos: Option<BytePos>,
+    prev: Option<BytePos>,
+    e: &impl Debug,
+    sources: &SourceMap,
+) -> ! {
+    match (locate(pos, sources), locate(prev, sources)) {
+        (Some(at), Some(prev)) => panic!("{} at {} (first defined at {}): {:?}", msg, at, prev, e),
+        (Some(at), None) => panic!("{} at {}: {:?}", msg, at, e),
+        _ => panic!("{}: {:?}", msg, e),
+    }
+}
+
+/// The file, line and column of a position, as `path:line:col`.
+fn locate(pos: Option<BytePos>, sources: &SourceMap) -> Option<String> {
+    let loc = sources.location(pos?)?;
+    Some(format!(
+        "{}:{}:{}",
+        sources.file(loc.file).path.display(),
+        loc.line,
+        loc.col
+    ))
+}
+
+/// Reports a warning like [`fail`], carrying on with compilation.
+fn warn(pos: BytePos, w: &impl Debug, sources: &SourceMap) {
+    match locate(Some(pos), sources) {
+        Some(at) => eprintln!("Warning at {}: {:?}", at, w),
+        None => eprintln!("Warning: {:?}", w),
+    }
+}
@@ -0,0 +1,22 @@
+module aliases ( 
+	input wire clk, 
+	input wire [3:0] req_addr, 
+	input wire [15:0] req_data, 
+	input wire we, 
+	output wire [15:0] rdata 
+); 
+	reg [15:0] ram [0:15]; 
+	wire [15:0] swap_0_w = req_data; 
+	wire [15:0] swap_0 = {swap_0_w[7:0], swap_0_w[15:8]}; 
+	always @(posedge clk) begin 
+		if (we) begin 
+			ram[req_addr] <= swap_0; 
+		end 
+	end 
+	wire [3:0] last = req_addr; 
+	reg [15:0] ram_rdata_0; 
+	always @(posedge clk) begin 
+		ram_rdata_0 <= ram[last]; 
+	end 
+	assign rdata = ram_rdata_0; 
+endmodule 
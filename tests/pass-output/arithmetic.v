@@ -0,0 +1,14 @@
+module arithmetic ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire signed [7:0] c, 
+	output wire [8:0] sum, 
+	output wire signed [8:0] diff, 
+	output wire [15:0] prod, 
+	output wire [7:0] next 
+); 
+	assign sum = a + b; 
+	assign diff = c - 9'sh3; 
+	assign prod = a * b; 
+	assign next = a + b * 8'h2; 
+endmodule 
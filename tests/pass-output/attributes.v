@@ -0,0 +1,42 @@
+(* keep *) module sync ( 
+	input wire clk, 
+	input wire din, 
+	output wire dout 
+); 
+	(* async_reg *) reg meta; 
+	(* async_reg *) reg held; 
+	always @(posedge clk) begin 
+		meta <= din; 
+	end 
+	always @(posedge clk) begin 
+		held <= meta; 
+	end 
+	assign dout = held; 
+endmodule 
+
+module attributes ( 
+	input wire clk, 
+	(* mark_debug = "true" *) input wire bus_valid, 
+	(* mark_debug = "true" *) input wire [7:0] bus_data, 
+	input wire ext, 
+	output wire synced, 
+	output wire [7:0] rdata 
+); 
+	sync s ( 
+		.clk(clk), 
+		.din(ext), 
+		.dout(synced) 
+	); 
+	(* ram_style = "block" *) reg [7:0] store [0:255]; 
+	always @(posedge clk) begin 
+		if (bus_valid) begin 
+			store[bus_data] <= bus_data; 
+		end 
+	end 
+	(* keep *) wire [7:0] addr = bus_data; 
+	reg [7:0] store_rdata_0; 
+	always @(posedge clk) begin 
+		store_rdata_0 <= store[addr]; 
+	end 
+	assign rdata = store_rdata_0; 
+endmodule 
@@ -0,0 +1,13 @@
+module average ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire signed [7:0] s, 
+	input wire signed [7:0] t, 
+	output wire [7:0] avg, 
+	output wire signed [7:0] savg 
+); 
+	wire [8:0] wide_0 = a + b; 
+	assign avg = wide_0 >> 1'h1; 
+	wire signed [8:0] wide_1 = s + t; 
+	assign savg = wide_1 >>> 1'h1; 
+endmodule 
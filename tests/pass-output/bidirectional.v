@@ -0,0 +1,42 @@
+module pad ( 
+	input wire oe, 
+	input wire [7:0] dout, 
+	output wire [7:0] din, 
+	inout wire [7:0] bus 
+); 
+	assign bus = oe ? dout : 8'bz; 
+	assign din = bus; 
+endmodule 
+
+module i2c_master ( 
+	input wire clk, 
+	input wire hold, 
+	inout wire port_scl, 
+	inout wire port_sda 
+); 
+	assign port_scl = !hold ? 1'h0 : 1'bz; 
+	assign port_sda = hold ? 1'h0 : 1'bz; 
+endmodule 
+
+module bidirectional ( 
+	input wire clk, 
+	input wire oe, 
+	input wire [7:0] dout, 
+	output wire [7:0] din, 
+	inout wire [7:0] data, 
+	inout wire i2c_scl, 
+	inout wire i2c_sda 
+); 
+	pad p ( 
+		.oe(oe), 
+		.dout(dout), 
+		.din(din), 
+		.bus(data) 
+	); 
+	i2c_master m ( 
+		.clk(clk), 
+		.hold(oe), 
+		.port_scl(i2c_scl), 
+		.port_sda(i2c_sda) 
+	); 
+endmodule 
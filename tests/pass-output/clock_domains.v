@@ -0,0 +1,28 @@
+module clock_domains ( 
+	input wire fast_clk, 
+	input wire slow_clk, 
+	input wire arst, 
+	input wire [7:0] d, 
+	output wire slow_clk_out, 
+	output wire [7:0] q 
+); 
+	reg [7:0] fast; 
+	always @(posedge fast_clk or posedge arst) begin 
+		if (arst) begin 
+			fast <= 8'h0; 
+		end else begin 
+			fast <= d; 
+		end 
+	end 
+	wire clk = slow_clk; 
+	assign slow_clk_out = clk; 
+	reg [7:0] meta; 
+	always @(posedge clk) begin 
+		meta <= fast; 
+	end 
+	reg [7:0] stable; 
+	always @(posedge slow_clk) begin 
+		stable <= meta; 
+	end 
+	assign q = stable; 
+endmodule 
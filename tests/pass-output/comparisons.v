@@ -0,0 +1,17 @@
+module comparisons ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire signed [7:0] c, 
+	input wire en, 
+	output wire eq, 
+	output wire lt, 
+	output wire neg, 
+	output wire masked, 
+	output wire valid 
+); 
+	assign eq = a == b; 
+	assign lt = a < b || a <= 8'h4; 
+	assign neg = c < 8'sh0; 
+	assign masked = ( a & 8'hF ) != b >> 3'h4; 
+	assign valid = en && !( a >= b ) && a > 8'h0; 
+endmodule 
@@ -0,0 +1,11 @@
+module concat_repeat ( 
+	input wire [7:0] a, 
+	input wire [3:0] b, 
+	output wire [11:0] x, 
+	output wire [15:0] y, 
+	output wire [7:0] z 
+); 
+	assign x = {a, b}; 
+	assign y = {{8{a[7]}}, a}; 
+	assign z = {2{b[1:0], 2'h2}}; 
+endmodule 
@@ -0,0 +1,27 @@
+module scale #( 
+	parameter SHIFT = 1 
+) ( 
+	input wire [7:0] x, 
+	output wire [(8 + SHIFT)-1:0] y 
+); 
+	localparam signed [3:0] OFFSET = 4'shD; 
+	localparam STEP = SHIFT * 2; 
+	assign y = x + STEP; 
+endmodule 
+
+module consts ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	output wire [7:0] lo, 
+	output wire [3:0] hi, 
+	output wire [9:0] scaled 
+); 
+	localparam [7:0] MASK = 8'hF; 
+	localparam [7:0] LIMIT = 8'h8; 
+	assign lo = a & MASK; 
+	assign hi = a[7:4]; 
+	scale #(.SHIFT(2)) s ( 
+		.x(b), 
+		.y(scaled) 
+	); 
+endmodule 
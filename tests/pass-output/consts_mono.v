@@ -0,0 +1,34 @@
+module scale ( 
+	input wire [7:0] x, 
+	output wire [8:0] y 
+); 
+	localparam signed [3:0] OFFSET = 4'shD; 
+	localparam STEP = 2; 
+	assign y = x + 9'h2; 
+endmodule 
+
+module consts ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	output wire [7:0] lo, 
+	output wire [3:0] hi, 
+	output wire [9:0] scaled 
+); 
+	localparam [7:0] MASK = 8'hF; 
+	localparam [7:0] LIMIT = 8'h8; 
+	assign lo = a & MASK; 
+	assign hi = a[7:4]; 
+	scale_SHIFT_2 s ( 
+		.x(b), 
+		.y(scaled) 
+	); 
+endmodule 
+
+module scale_SHIFT_2 ( 
+	input wire [7:0] x, 
+	output wire [9:0] y 
+); 
+	localparam signed [3:0] OFFSET = 4'shD; 
+	localparam STEP = 4; 
+	assign y = x + 10'h4; 
+endmodule 
@@ -0,0 +1,48 @@
+module enums ( 
+	input wire clk, 
+	input wire rst, 
+	input wire start, 
+	input wire finish, 
+	output wire busy, 
+	output wire [2:0] light, 
+	output wire [2:0] phase 
+); 
+	localparam [1:0] State_Idle = 2'h0; 
+	localparam [1:0] State_Busy = 2'h1; 
+	localparam [1:0] State_Done = 2'h2; 
+	localparam [2:0] Light_Red = 3'h1; 
+	localparam [2:0] Light_Amber = 3'h2; 
+	localparam [2:0] Light_Green = 3'h4; 
+	localparam [2:0] Phase_A = 3'h0; 
+	localparam [2:0] Phase_B = 3'h1; 
+	localparam [2:0] Phase_C = 3'h3; 
+	localparam [2:0] Phase_D = 3'h2; 
+	localparam [2:0] Phase_E = 3'h6; 
+	reg [1:0] state; 
+	reg [1:0] match_0; 
+	always @* begin 
+		case (state) 
+			State_Idle: match_0 = start ? State_Busy : State_Idle; 
+			State_Busy: match_0 = finish ? State_Done : State_Busy; 
+			default: match_0 = State_Idle; 
+		endcase 
+	end 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			state <= State_Idle; 
+		end else begin 
+			state <= match_0; 
+		end 
+	end 
+	assign busy = state == State_Busy; 
+	reg [2:0] match_1; 
+	always @* begin 
+		case (state) 
+			State_Busy: match_1 = Light_Amber; 
+			State_Done: match_1 = Light_Green; 
+			default: match_1 = Light_Red; 
+		endcase 
+	end 
+	assign light = match_1; 
+	assign phase = state != State_Idle ? Phase_E : Phase_A; 
+endmodule 
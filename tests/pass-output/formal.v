@@ -0,0 +1,26 @@
+module formal ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [3:0] req, 
+	output wire [3:0] grant, 
+	output wire busy 
+); 
+	reg [3:0] count; 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			count <= 4'h0; 
+		end else begin 
+			count <= busy ? count - 4'h1 : 4'h3; 
+		end 
+	end 
+	assign busy = count != 4'h0; 
+	assign grant = req & 4'h1; 
+	`ifdef FORMAL 
+	always @* begin 
+		assert (( grant & ~req ) == 4'h0); 
+	end 
+	assert property (@(posedge clk) disable iff (rst) count <= 4'h3); 
+	assume property (@(posedge clk) req != 4'hF); 
+	cover property (@(posedge clk) busy && grant != 4'h0); 
+	`endif 
+endmodule 
@@ -0,0 +1,42 @@
+module functions ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire [3:0] req, 
+	output wire p, 
+	output wire [1:0] grant, 
+	output wire [7:0] total 
+); 
+	wire [7:0] parity_0_x = a; 
+	wire parity_0 = ^parity_0_x; 
+	wire [7:0] parity_1_x = b; 
+	wire parity_1 = ^parity_1_x; 
+	assign p = parity_0 ^ parity_1; 
+	wire [3:0] priority_0_req = req; 
+	wire [1:0] priority_0_hi = priority_0_req[3:2]; 
+	reg [1:0] match_1; 
+	always @* begin 
+		case (priority_0_req[1:0]) 
+			2'h2, 2'h3: match_1 = 2'h1; 
+			default: match_1 = 2'h0; 
+		endcase 
+	end 
+	reg [1:0] match_0; 
+	always @* begin 
+		case (priority_0_hi) 
+			2'h0: match_0 = match_1; 
+			2'h1: match_0 = 2'h2; 
+			default: match_0 = 2'h3; 
+		endcase 
+	end 
+	wire [1:0] priority_0 = match_0; 
+	assign grant = priority_0; 
+	wire [7:0] saturate_0_a = a; 
+	wire [7:0] saturate_0_b = b; 
+	wire [8:0] saturate_0_sum = saturate_0_a + saturate_0_b; 
+	wire [7:0] saturate_0 = saturate_0_sum > 9'hC8 ? 8'hC8 : saturate_0_sum[7:0]; 
+	wire [7:0] saturate_1_a = saturate_0; 
+	wire [7:0] saturate_1_b = b; 
+	wire [8:0] saturate_1_sum = saturate_1_a + saturate_1_b; 
+	wire [7:0] saturate_1 = saturate_1_sum > 9'hC8 ? 8'hC8 : saturate_1_sum[7:0]; 
+	assign total = saturate_1; 
+endmodule 
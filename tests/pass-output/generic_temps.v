@@ -0,0 +1,60 @@
+module avg ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire [1:0] sel, 
+	output wire [7:0] y 
+); 
+	wire [8:0] wide_0 = a + b; 
+	wire [7:0] mean = wide_0 >> 1'h1; 
+	reg [7:0] match_1; 
+	always @* begin 
+		case (sel) 
+			2'h0: match_1 = a; 
+			2'h1: match_1 = b; 
+			default: match_1 = mean; 
+		endcase 
+	end 
+	assign y = match_1; 
+endmodule 
+
+module generic_temps ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire [15:0] c, 
+	input wire [15:0] d, 
+	input wire [1:0] sel, 
+	output wire [7:0] x, 
+	output wire [15:0] y 
+); 
+	avg narrow ( 
+		.a(a), 
+		.b(b), 
+		.sel(sel), 
+		.y(x) 
+	); 
+	avg_W_16 wide ( 
+		.a(c), 
+		.b(d), 
+		.sel(sel), 
+		.y(y) 
+	); 
+endmodule 
+
+module avg_W_16 ( 
+	input wire [15:0] a, 
+	input wire [15:0] b, 
+	input wire [1:0] sel, 
+	output wire [15:0] y 
+); 
+	wire [16:0] wide_0 = a + b; 
+	wire [15:0] mean = wide_0 >> 1'h1; 
+	reg [15:0] match_1; 
+	always @* begin 
+		case (sel) 
+			2'h0: match_1 = a; 
+			2'h1: match_1 = b; 
+			default: match_1 = mean; 
+		endcase 
+	end 
+	assign y = match_1; 
+endmodule 
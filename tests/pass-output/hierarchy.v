@@ -0,0 +1,55 @@
+module full_adder ( 
+	input wire a, 
+	input wire b, 
+	input wire cin, 
+	output wire sum, 
+	output wire cout 
+); 
+	assign sum = a ^ b ^ cin; 
+	assign cout = a & b | cin & ( a ^ b ); 
+endmodule 
+
+module adder2 ( 
+	input wire [1:0] a, 
+	input wire [1:0] b, 
+	output wire [2:0] sum 
+); 
+	wire carry; 
+	wire cout; 
+	wire msb; 
+	wire lsb; 
+	full_adder fa0 ( 
+		.a(a[0]), 
+		.b(b[0]), 
+		.cin(1'h0), 
+		.sum(lsb), 
+		.cout(carry) 
+	); 
+	full_adder fa1 ( 
+		.a(a[1]), 
+		.b(b[1]), 
+		.cin(carry), 
+		.sum(msb), 
+		.cout(cout) 
+	); 
+	assign sum = {cout, msb, lsb}; 
+endmodule 
+
+module hierarchy ( 
+	input wire clk, 
+	input wire [1:0] x, 
+	input wire [1:0] y, 
+	output wire [2:0] z 
+); 
+	wire [2:0] sum; 
+	adder2 add ( 
+		.a(x), 
+		.b(y), 
+		.sum(sum) 
+	); 
+	reg [2:0] out_reg; 
+	always @(posedge clk) begin 
+		out_reg <= sum; 
+	end 
+	assign z = out_reg; 
+endmodule 
@@ -0,0 +1,50 @@
+module imports ( 
+	input wire clk, 
+	input wire [7:0] d, 
+	input wire async_in, 
+	output wire [7:0] q, 
+	output wire p, 
+	output wire synced 
+); 
+	byte_reg held ( 
+		.clk(clk), 
+		.d(d), 
+		.q(q) 
+	); 
+	wire [7:0] parity_0_x = d; 
+	wire parity_0 = ^parity_0_x; 
+	assign p = parity_0; 
+	sync2 sync ( 
+		.clk(clk), 
+		.d(async_in), 
+		.q(synced) 
+	); 
+endmodule 
+
+module byte_reg ( 
+	input wire clk, 
+	input wire [7:0] d, 
+	output wire [7:0] q 
+); 
+	reg [7:0] r; 
+	always @(posedge clk) begin 
+		r <= d; 
+	end 
+	assign q = r; 
+endmodule 
+
+module sync2 ( 
+	input wire clk, 
+	input wire d, 
+	output wire q 
+); 
+	reg meta; 
+	reg stable; 
+	always @(posedge clk) begin 
+		meta <= d; 
+	end 
+	always @(posedge clk) begin 
+		stable <= meta; 
+	end 
+	assign q = stable; 
+endmodule 
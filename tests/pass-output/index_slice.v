@@ -0,0 +1,12 @@
+module index_slice ( 
+	input wire [15:0] a, 
+	input wire [3:0] sel, 
+	output wire x, 
+	output wire y, 
+	output wire [7:0] z 
+); 
+	assign x = a[15]; 
+	assign y = a[sel] ^ a[0]; 
+	wire [7:0] hi = a[15:8]; 
+	assign z = hi & a[7:0]; 
+endmodule 
@@ -0,0 +1,71 @@
+module producer ( 
+	input wire clk, 
+	output wire tx_valid, 
+	input wire tx_ready, 
+	output wire [7:0] tx_data 
+); 
+	reg [7:0] count = 8'h0; 
+	always @(posedge clk) begin 
+		count <= count + 8'h1; 
+	end 
+	assign tx_valid = tx_ready; 
+	assign tx_data = count; 
+endmodule 
+
+module consumer ( 
+	input wire rx_valid, 
+	output wire rx_ready, 
+	input wire [7:0] rx_data, 
+	output wire [7:0] seen 
+); 
+	assign rx_ready = 1'h1; 
+	assign seen = rx_valid ? rx_data : 8'h0; 
+endmodule 
+
+module echo ( 
+	input wire port_req_valid, 
+	output wire port_req_ready, 
+	input wire [7:0] port_req_data, 
+	output wire port_resp_valid, 
+	input wire port_resp_ready, 
+	output wire [7:0] port_resp_data 
+); 
+	assign port_req_ready = port_resp_ready; 
+	assign port_resp_valid = port_req_valid; 
+	assign port_resp_data = port_req_data; 
+endmodule 
+
+module interfaces ( 
+	input wire clk, 
+	output wire [7:0] seen, 
+	input wire link_req_valid, 
+	output wire link_req_ready, 
+	input wire [7:0] link_req_data, 
+	output wire link_resp_valid, 
+	input wire link_resp_ready, 
+	output wire [7:0] link_resp_data 
+); 
+	wire stream_valid; 
+	wire stream_ready; 
+	wire [7:0] stream_data; 
+	producer p ( 
+		.clk(clk), 
+		.tx_valid(stream_valid), 
+		.tx_ready(stream_ready), 
+		.tx_data(stream_data) 
+	); 
+	consumer c ( 
+		.rx_valid(stream_valid), 
+		.rx_ready(stream_ready), 
+		.rx_data(stream_data), 
+		.seen(seen) 
+	); 
+	echo e ( 
+		.port_req_valid(link_req_valid), 
+		.port_req_ready(link_req_ready), 
+		.port_req_data(link_req_data), 
+		.port_resp_valid(link_resp_valid), 
+		.port_resp_ready(link_resp_ready), 
+		.port_resp_data(link_resp_data) 
+	); 
+endmodule 
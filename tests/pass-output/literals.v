@@ -0,0 +1,11 @@
+module literals ( 
+	input wire [15:0] a, 
+	input wire signed [7:0] b, 
+	output wire [15:0] x, 
+	output wire signed [7:0] y, 
+	output wire [11:0] z 
+); 
+	assign x = a & 16'hFF00 | 16'hA | 16'h3E8; 
+	assign y = b & 8'sh7F; 
+	assign z = 12'hF | 12'h5; 
+endmodule 
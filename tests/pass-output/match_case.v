@@ -0,0 +1,28 @@
+module match_case ( 
+	input wire [1:0] op, 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	output wire [7:0] y, 
+	output wire [3:0] onehot 
+); 
+	reg [7:0] match_0; 
+	always @* begin 
+		case (op) 
+			2'h0: match_0 = a & b; 
+			2'h1: match_0 = a | b; 
+			2'h2, 2'h3: match_0 = a ^ b; 
+			default: match_0 = 8'h0; 
+		endcase 
+	end 
+	assign y = match_0; 
+	reg [3:0] match_1; 
+	always @* begin 
+		case (op) 
+			2'h0: match_1 = 4'h1; 
+			2'h1: match_1 = 4'h2; 
+			2'h2: match_1 = 4'h4; 
+			default: match_1 = 4'h8; 
+		endcase 
+	end 
+	assign onehot = match_1; 
+endmodule 
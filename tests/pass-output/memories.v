@@ -0,0 +1,65 @@
+module ram #( 
+	parameter DEPTH = 16 
+) ( 
+	input wire clk, 
+	input wire we, 
+	input wire [3:0] waddr, 
+	input wire [31:0] wdata, 
+	input wire [3:0] raddr, 
+	output wire [31:0] rdata 
+); 
+	reg [31:0] store [0:DEPTH - 1]; 
+	always @(posedge clk) begin 
+		if (we) begin 
+			store[waddr] <= wdata; 
+		end 
+	end 
+	reg [31:0] store_rdata_0; 
+	always @(posedge clk) begin 
+		store_rdata_0 <= store[raddr]; 
+	end 
+	assign rdata = store_rdata_0; 
+endmodule 
+
+module memories ( 
+	input wire clk, 
+	input wire [1:0] addr, 
+	input wire we, 
+	input wire [31:0] wdata, 
+	output wire [31:0] rdata, 
+	output wire [7:0] square, 
+	output wire [15:0] coeff 
+); 
+	ram mem0 ( 
+		.clk(clk), 
+		.we(we), 
+		.waddr({2'h0, addr}), 
+		.wdata(wdata), 
+		.raddr({2'h0, addr}), 
+		.rdata(rdata) 
+	); 
+	reg [7:0] rom [0:3]; 
+	initial begin 
+		rom[0] = 8'h0; 
+		rom[1] = 8'h1; 
+		rom[2] = 8'h4; 
+		rom[3] = 8'h9; 
+	end 
+	reg [7:0] rom_rdata_0; 
+	always @(posedge clk) begin 
+		rom_rdata_0 <= rom[addr]; 
+	end 
+	assign square = rom_rdata_0; 
+	reg [15:0] coeffs [0:1023]; 
+	initial begin 
+		$readmemh("coeffs.hex", coeffs); 
+	end 
+	always @(posedge clk) begin 
+		coeffs[0] <= 16'h1234; 
+	end 
+	reg [15:0] coeffs_rdata_1; 
+	always @(posedge clk) begin 
+		coeffs_rdata_1 <= coeffs[{8'h0, addr}]; 
+	end 
+	assign coeff = coeffs_rdata_1; 
+endmodule 
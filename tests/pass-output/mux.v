@@ -0,0 +1,13 @@
+module mux ( 
+	input wire [1:0] sel, 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire [7:0] c, 
+	output wire [7:0] x, 
+	output wire [7:0] y, 
+	output wire [7:0] z 
+); 
+	assign x = sel[0] ? a : b; 
+	assign y = sel == 2'h0 ? a : sel == 2'h1 ? b : 8'h0; 
+	assign z = c & ( sel[1] ? a : ~a ); 
+endmodule 
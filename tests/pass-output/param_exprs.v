@@ -0,0 +1,34 @@
+module rep #( 
+	parameter W = 8 
+) ( 
+	input wire a, 
+	input wire [W-1:0] b, 
+	output wire [W-1:0] y, 
+	output wire msb 
+); 
+	assign y = {W{a}} ^ b; 
+	assign msb = b[W - 1]; 
+endmodule 
+
+module param_exprs ( 
+	input wire a, 
+	input wire [7:0] b, 
+	input wire [3:0] c, 
+	output wire [7:0] x, 
+	output wire [3:0] y, 
+	output wire bmsb, 
+	output wire cmsb 
+); 
+	rep wide ( 
+		.a(a), 
+		.b(b), 
+		.y(x), 
+		.msb(bmsb) 
+	); 
+	rep #(.W(4)) narrow ( 
+		.a(a), 
+		.b(c), 
+		.y(y), 
+		.msb(cmsb) 
+	); 
+endmodule 
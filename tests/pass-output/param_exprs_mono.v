@@ -0,0 +1,42 @@
+module rep ( 
+	input wire a, 
+	input wire [7:0] b, 
+	output wire [7:0] y, 
+	output wire msb 
+); 
+	assign y = {8{a}} ^ b; 
+	assign msb = b[7]; 
+endmodule 
+
+module param_exprs ( 
+	input wire a, 
+	input wire [7:0] b, 
+	input wire [3:0] c, 
+	output wire [7:0] x, 
+	output wire [3:0] y, 
+	output wire bmsb, 
+	output wire cmsb 
+); 
+	rep wide ( 
+		.a(a), 
+		.b(b), 
+		.y(x), 
+		.msb(bmsb) 
+	); 
+	rep_W_4 narrow ( 
+		.a(a), 
+		.b(c), 
+		.y(y), 
+		.msb(cmsb) 
+	); 
+endmodule 
+
+module rep_W_4 ( 
+	input wire a, 
+	input wire [3:0] b, 
+	output wire [3:0] y, 
+	output wire msb 
+); 
+	assign y = {4{a}} ^ b; 
+	assign msb = b[3]; 
+endmodule 
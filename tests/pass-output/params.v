@@ -0,0 +1,41 @@
+module acc #( 
+	parameter WIDTH = 8, 
+	parameter SUM_WIDTH = WIDTH + 1 
+) ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [WIDTH-1:0] d, 
+	output wire [SUM_WIDTH-1:0] sum 
+); 
+	reg [SUM_WIDTH-1:0] total; 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			total <= 9'h0; 
+		end else begin 
+			total <= total + d; 
+		end 
+	end 
+	assign sum = total; 
+endmodule 
+
+module params ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [7:0] a, 
+	input wire [3:0] b, 
+	output wire [8:0] x, 
+	output wire [5:0] y 
+); 
+	acc wide ( 
+		.clk(clk), 
+		.rst(rst), 
+		.d(a), 
+		.sum(x) 
+	); 
+	acc #(.WIDTH(4), .SUM_WIDTH(6)) narrow ( 
+		.clk(clk), 
+		.rst(rst), 
+		.d(b), 
+		.sum(y) 
+	); 
+endmodule 
@@ -0,0 +1,55 @@
+module acc ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [7:0] d, 
+	output wire [8:0] sum 
+); 
+	reg [8:0] total; 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			total <= 9'h0; 
+		end else begin 
+			total <= total + d; 
+		end 
+	end 
+	assign sum = total; 
+endmodule 
+
+module params ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [7:0] a, 
+	input wire [3:0] b, 
+	output wire [8:0] x, 
+	output wire [5:0] y 
+); 
+	acc wide ( 
+		.clk(clk), 
+		.rst(rst), 
+		.d(a), 
+		.sum(x) 
+	); 
+	acc_WIDTH_4_SUM_WIDTH_6 narrow ( 
+		.clk(clk), 
+		.rst(rst), 
+		.d(b), 
+		.sum(y) 
+	); 
+endmodule 
+
+module acc_WIDTH_4_SUM_WIDTH_6 ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [3:0] d, 
+	output wire [5:0] sum 
+); 
+	reg [5:0] total; 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			total <= 6'h0; 
+		end else begin 
+			total <= total + d; 
+		end 
+	end 
+	assign sum = total; 
+endmodule 
@@ -0,0 +1,34 @@
+module registers ( 
+	input wire clk, 
+	input wire rst, 
+	input wire [7:0] d, 
+	output wire [7:0] count, 
+	output wire [7:0] q, 
+	output wire [7:0] tag 
+); 
+	reg [7:0] value; 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			value <= 8'h0; 
+		end else begin 
+			value <= value + 8'h1; 
+		end 
+	end 
+	assign count = value; 
+	reg [7:0] delay = 8'hA5; 
+	always @(posedge clk) begin 
+		delay <= d; 
+	end 
+	reg [7:0] last; 
+	always @(posedge clk) begin 
+		last <= delay; 
+	end 
+	assign q = last; 
+	reg [7:0] seed; 
+	always @(posedge clk) begin 
+		if (rst) begin 
+			seed <= 8'h3C; 
+		end 
+	end 
+	assign tag = seed; 
+endmodule 
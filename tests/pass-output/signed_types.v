@@ -0,0 +1,13 @@
+module signed_types ( 
+	input wire signed [7:0] a, 
+	input wire [7:0] b, 
+	input wire [7:0] c, 
+	output wire signed [7:0] x, 
+	output wire [7:0] y, 
+	output wire [7:0] z 
+); 
+	wire signed [7:0] t = a >>> 2'h2; 
+	assign x = t << 1'h1; 
+	assign y = b >> 2'h2; 
+	assign z = c >> 2'h2 & 8'hFF; 
+endmodule 
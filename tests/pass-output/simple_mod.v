@@ -7,7 +7,7 @@ module simple_mod (
 	output wire y, 
 	output wire z 
 ); 
-	assign x = a << 1 >> 1 & b ^ c | d; 
+	assign x = a << 1'h1 >> 1'h1 & b ^ c | d; 
 	assign y = ~a & &a & ^a & |a; 
 	assign z = ( a & b ) | c; 
 endmodule 
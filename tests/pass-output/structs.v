@@ -0,0 +1,64 @@
+module stage ( 
+	input wire clk, 
+	input wire [0:0] din_header_kind, 
+	input wire [7:0] din_header_addr, 
+	input wire [15:0] din_data, 
+	input wire din_last, 
+	output wire [0:0] dout_header_kind, 
+	output wire [7:0] dout_header_addr, 
+	output wire [15:0] dout_data, 
+	output wire dout_last 
+); 
+	reg [0:0] held_header_kind; 
+	reg [7:0] held_header_addr; 
+	reg [15:0] held_data; 
+	reg held_last; 
+	always @(posedge clk) begin 
+		held_header_kind <= din_header_kind; 
+		held_header_addr <= din_header_addr; 
+		held_data <= din_data; 
+		held_last <= din_last; 
+	end 
+	assign dout_header_kind = held_header_kind; 
+	assign dout_header_addr = held_header_addr; 
+	assign dout_data = held_data; 
+	assign dout_last = held_last; 
+endmodule 
+
+module structs ( 
+	input wire clk, 
+	input wire [0:0] req_header_kind, 
+	input wire [7:0] req_header_addr, 
+	input wire [15:0] req_data, 
+	input wire req_last, 
+	output wire [0:0] resp_header_kind, 
+	output wire [7:0] resp_header_addr, 
+	output wire [15:0] resp_data, 
+	output wire resp_last, 
+	output wire is_write, 
+	output wire [7:0] addr 
+); 
+	localparam [0:0] Kind_Read = 1'h0; 
+	localparam [0:0] Kind_Write = 1'h1; 
+	wire [0:0] staged_header_kind; 
+	wire [7:0] staged_header_addr; 
+	wire [15:0] staged_data; 
+	wire staged_last; 
+	stage stage0 ( 
+		.clk(clk), 
+		.din_header_kind(req_header_kind), 
+		.din_header_addr(req_header_addr), 
+		.din_data(req_data), 
+		.din_last(req_last), 
+		.dout_header_kind(staged_header_kind), 
+		.dout_header_addr(staged_header_addr), 
+		.dout_data(staged_data), 
+		.dout_last(staged_last) 
+	); 
+	assign resp_header_kind = staged_header_kind; 
+	assign resp_header_addr = staged_header_addr; 
+	assign resp_data = staged_data ^ 16'hFFFF; 
+	assign resp_last = staged_last; 
+	assign is_write = req_header_kind == Kind_Write; 
+	assign addr = req_header_addr; 
+endmodule 
@@ -0,0 +1,50 @@
+module lane ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	output wire [7:0] y 
+); 
+	assign y = a ^ b; 
+endmodule 
+
+module unrolled ( 
+	input wire clk, 
+	input wire [31:0] a, 
+	input wire [31:0] b, 
+	output wire [7:0] total, 
+	output wire [31:0] y 
+); 
+	wire [7:0] x_0; 
+	lane lane_0 ( 
+		.a(a[7:0]), 
+		.b(b[7:0]), 
+		.y(x_0) 
+	); 
+	wire [7:0] x_1; 
+	lane lane_1 ( 
+		.a(a[15:8]), 
+		.b(b[15:8]), 
+		.y(x_1) 
+	); 
+	wire [7:0] x_2; 
+	lane lane_2 ( 
+		.a(a[23:16]), 
+		.b(b[23:16]), 
+		.y(x_2) 
+	); 
+	wire [7:0] x_3; 
+	lane lane_3 ( 
+		.a(a[31:24]), 
+		.b(b[31:24]), 
+		.y(x_3) 
+	); 
+	assign y = {x_3, x_2, x_1, x_0}; 
+	wire [7:0] sum_0 = x_0; 
+	wire [7:0] sum_1 = sum_0 + x_1; 
+	wire [7:0] sum_2 = sum_1 + x_2; 
+	wire [7:0] sum_3 = sum_2 + x_3; 
+	reg [7:0] held; 
+	always @(posedge clk) begin 
+		held <= sum_3; 
+	end 
+	assign total = held; 
+endmodule 
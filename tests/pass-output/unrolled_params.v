@@ -0,0 +1,37 @@
+module parity ( 
+	input wire [3:0] a, 
+	output wire p 
+); 
+	wire p_0 = a[0]; 
+	wire p_1 = p_0 ^ a[1]; 
+	wire p_2 = p_1 ^ a[2]; 
+	wire p_3 = p_2 ^ a[3]; 
+	localparam LAST = 3; 
+	assign p = p_3; 
+endmodule 
+
+module unrolled_params ( 
+	input wire [3:0] a, 
+	input wire [1:0] b, 
+	output wire x, 
+	output wire y 
+); 
+	parity wide ( 
+		.a(a), 
+		.p(x) 
+	); 
+	parity_WIDTH_2 narrow ( 
+		.a(b), 
+		.p(y) 
+	); 
+endmodule 
+
+module parity_WIDTH_2 ( 
+	input wire [1:0] a, 
+	output wire p 
+); 
+	wire p_0 = a[0]; 
+	wire p_1 = p_0 ^ a[1]; 
+	localparam LAST = 1; 
+	assign p = p_1; 
+endmodule 
@@ -0,0 +1,15 @@
+module vector_types ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire c, 
+	output wire [7:0] x, 
+	output wire [0:0] y, 
+	output wire z 
+); 
+	wire [7:0] t = a & b; 
+	assign x = t | a; 
+	wire [0:0] u; 
+	assign u = c; 
+	assign y = u; 
+	assign z = c; 
+endmodule 
@@ -0,0 +1,23 @@
+module wide_compare ( 
+	input wire [7:0] a, 
+	input wire [7:0] b, 
+	input wire [7:0] c, 
+	input wire signed [7:0] s, 
+	input wire signed [7:0] t, 
+	output wire carry_eq, 
+	output wire borrow_lt, 
+	output wire prod_gt, 
+	output wire signed_ge, 
+	output wire masked 
+); 
+	wire [8:0] wide_0 = a + b; 
+	assign carry_eq = wide_0 == c; 
+	wire [8:0] wide_1 = a - b; 
+	assign borrow_lt = wide_1 < c; 
+	wire [15:0] wide_2 = a * b; 
+	assign prod_gt = wide_2 > 16'h3E8; 
+	wire signed [8:0] wide_3 = s + t; 
+	assign signed_ge = wide_3 >= 9'sh0; 
+	wire [8:0] wide_4 = ( a + b ) & 9'h100; 
+	assign masked = wide_4 != 9'h0; 
+endmodule 
@@ -0,0 +1,24 @@
+module pad #( 
+	parameter W = 4 
+) ( 
+	input wire [W-1:0] a, 
+	output wire [(W > 2 ? W : 2)-1:0] y 
+); 
+	assign y = a; 
+endmodule 
+
+module width_exprs ( 
+	input wire [3:0] a, 
+	input wire [1:0] b, 
+	output wire [3:0] x, 
+	output wire [1:0] y 
+); 
+	pad wide ( 
+		.a(a), 
+		.y(x) 
+	); 
+	pad #(.W(2)) narrow ( 
+		.a(b), 
+		.y(y) 
+	); 
+endmodule 
@@ -10,7 +10,402 @@ fn simple_mod() {
     );
 }
 
+#[test]
+fn vector_types() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/vector_types.si",
+        "./tests/pass-output/vector_types.v",
+    );
+}
+
+#[test]
+fn signed_types() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/signed_types.si",
+        "./tests/pass-output/signed_types.v",
+    );
+}
+
+#[test]
+fn literals() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/literals.si",
+        "./tests/pass-output/literals.v",
+    );
+}
+
+#[test]
+fn index_slice() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/index_slice.si",
+        "./tests/pass-output/index_slice.v",
+    );
+}
+
+#[test]
+fn concat_repeat() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/concat_repeat.si",
+        "./tests/pass-output/concat_repeat.v",
+    );
+}
+
+#[test]
+fn arithmetic() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/arithmetic.si",
+        "./tests/pass-output/arithmetic.v",
+    );
+}
+
+#[test]
+fn comparisons() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/comparisons.si",
+        "./tests/pass-output/comparisons.v",
+    );
+}
+
+#[test]
+fn mux() {
+    assert_compiled_output_eq("./tests/pass-output/mux.si", "./tests/pass-output/mux.v");
+}
+
+#[test]
+fn match_case() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/match_case.si",
+        "./tests/pass-output/match_case.v",
+    );
+}
+
+#[test]
+fn registers() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/registers.si",
+        "./tests/pass-output/registers.v",
+    );
+}
+
+#[test]
+fn clock_domains() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/clock_domains.si",
+        "./tests/pass-output/clock_domains.v",
+    );
+}
+
+#[test]
+fn hierarchy() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/hierarchy.si",
+        "./tests/pass-output/hierarchy.v",
+    );
+}
+
+#[test]
+fn params() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/params.si",
+        "./tests/pass-output/params.v",
+    );
+    assert_compiled_output_with_flags_eq(
+        "./tests/pass-output/params.si",
+        "./tests/pass-output/params_mono.v",
+        &["--monomorphize"],
+    );
+}
+
+#[test]
+fn consts() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/consts.si",
+        "./tests/pass-output/consts.v",
+    );
+    assert_compiled_output_with_flags_eq(
+        "./tests/pass-output/consts.si",
+        "./tests/pass-output/consts_mono.v",
+        &["--monomorphize"],
+    );
+}
+
+#[test]
+fn enums() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/enums.si",
+        "./tests/pass-output/enums.v",
+    );
+}
+
+#[test]
+fn structs() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/structs.si",
+        "./tests/pass-output/structs.v",
+    );
+}
+
+#[test]
+fn interfaces() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/interfaces.si",
+        "./tests/pass-output/interfaces.v",
+    );
+}
+
+#[test]
+fn memories() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/memories.si",
+        "./tests/pass-output/memories.v",
+    );
+}
+
+#[test]
+fn bidirectional() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/bidirectional.si",
+        "./tests/pass-output/bidirectional.v",
+    );
+}
+
+#[test]
+fn unrolled() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/unrolled.si",
+        "./tests/pass-output/unrolled.v",
+    );
+}
+
+#[test]
+fn unrolled_params() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/unrolled_params.si",
+        "./tests/pass-output/unrolled_params.v",
+    );
+}
+
+#[test]
+fn functions() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/functions.si",
+        "./tests/pass-output/functions.v",
+    );
+}
+
+#[test]
+fn aliases() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/aliases.si",
+        "./tests/pass-output/aliases.v",
+    );
+}
+
+#[test]
+fn imports() {
+    assert_compiled_output_with_flags_eq(
+        "./tests/pass-output/imports.si",
+        "./tests/pass-output/imports.v",
+        &["-L", "./tests/include"],
+    );
+}
+
+#[test]
+fn module_paths() {
+    assert_compiled_output_with_flags_eq(
+        "./tests/pass-output/module_paths.si",
+        "./tests/pass-output/module_paths.v",
+        &["-L", "./tests/include"],
+    );
+}
+
+#[test]
+fn formal() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/formal.si",
+        "./tests/pass-output/formal.v",
+    );
+}
+
+#[test]
+fn attributes() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/attributes.si",
+        "./tests/pass-output/attributes.v",
+    );
+}
+
+#[test]
+fn wide_compare() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/wide_compare.si",
+        "./tests/pass-output/wide_compare.v",
+    );
+}
+
+#[test]
+fn average() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/average.si",
+        "./tests/pass-output/average.v",
+    );
+}
+
+#[test]
+fn param_exprs() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/param_exprs.si",
+        "./tests/pass-output/param_exprs.v",
+    );
+    assert_compiled_output_with_flags_eq(
+        "./tests/pass-output/param_exprs.si",
+        "./tests/pass-output/param_exprs_mono.v",
+        &["--monomorphize"],
+    );
+}
+
+#[test]
+fn generic_temps() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/generic_temps.si",
+        "./tests/pass-output/generic_temps.v",
+    );
+}
+
+#[test]
+fn width_exprs() {
+    assert_compiled_output_eq(
+        "./tests/pass-output/width_exprs.si",
+        "./tests/pass-output/width_exprs.v",
+    );
+}
+
 #[test]
 fn simple_fail() {
     assert_compiler_passed("./tests/fail/simple_fail.si");
 }
+
+#[test]
+fn mixed_signedness_fail() {
+    assert_compiler_passed("./tests/fail/mixed_signedness.si");
+}
+
+#[test]
+fn index_out_of_bounds_fail() {
+    assert_compiler_passed("./tests/fail/index_out_of_bounds.si");
+}
+
+#[test]
+fn mux_width_fail() {
+    assert_compiler_passed("./tests/fail/mux_width.si");
+}
+
+#[test]
+fn non_exhaustive_match_fail() {
+    assert_compiler_passed("./tests/fail/non_exhaustive_match.si");
+}
+
+#[test]
+fn non_constant_init_fail() {
+    assert_compiler_passed("./tests/fail/non_constant_init.si");
+}
+
+#[test]
+fn clock_as_data_fail() {
+    assert_compiler_passed("./tests/fail/clock_as_data.si");
+}
+
+#[test]
+fn domain_crossing_fail() {
+    assert_compiler_passed("./tests/fail/domain_crossing.si");
+}
+
+#[test]
+fn missing_connection_fail() {
+    assert_compiler_passed("./tests/fail/missing_connection.si");
+}
+
+#[test]
+fn unknown_param_fail() {
+    assert_compiler_passed("./tests/fail/unknown_param.si");
+}
+
+#[test]
+fn division_by_zero_fail() {
+    assert_compiler_passed("./tests/fail/division_by_zero.si");
+}
+
+#[test]
+fn mixed_enums_fail() {
+    assert_compiler_passed("./tests/fail/mixed_enums.si");
+}
+
+#[test]
+fn struct_operation_fail() {
+    assert_compiler_passed("./tests/fail/struct_operation.si");
+}
+
+#[test]
+fn drive_input_field_fail() {
+    assert_compiler_passed("./tests/fail/drive_input_field.si");
+}
+
+#[test]
+fn memory_length_fail() {
+    assert_compiler_passed("./tests/fail/memory_length.si");
+}
+
+#[test]
+fn drive_inout_fail() {
+    assert_compiler_passed("./tests/fail/drive_inout.si");
+}
+
+#[test]
+fn loop_bound_fail() {
+    assert_compiler_passed("./tests/fail/loop_bound.si");
+}
+
+#[test]
+fn function_instance_fail() {
+    assert_compiler_passed("./tests/fail/function_instance.si");
+}
+
+#[test]
+fn alias_signedness_fail() {
+    assert_compiler_passed("./tests/fail/alias_signedness.si");
+}
+
+#[test]
+fn unresolved_use_fail() {
+    assert_compiler_passed("./tests/fail/unresolved_use.si");
+}
+
+#[test]
+fn unresolved_path_fail() {
+    assert_compiler_passed("./tests/fail/unresolved_path.si");
+}
+
+#[test]
+fn duplicate_module_fail() {
+    assert_compiler_passed("./tests/fail/duplicate_module.si");
+}
+
+#[test]
+fn assert_width_fail() {
+    assert_compiler_passed("./tests/fail/assert_width.si");
+}
+
+#[test]
+fn signed_literal_fail() {
+    assert_compiler_passed("./tests/fail/signed_literal.si");
+}
+
+#[test]
+fn param_index_fail() {
+    assert_compiler_passed("./tests/fail/param_index.si");
+}
+
+#[test]
+fn unroll_limit_fail() {
+    assert_compiler_passed("./tests/fail/unroll_limit.si");
+}
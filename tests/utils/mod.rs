@@ -4,6 +4,14 @@ use std::process::Command;
 use difference::Changeset;
 
 pub fn assert_compiled_output_eq(source_path: &str, expected_path: &str) {
+    assert_compiled_output_with_flags_eq(source_path, expected_path, &[]);
+}
+
+pub fn assert_compiled_output_with_flags_eq(
+    source_path: &str,
+    expected_path: &str,
+    flags: &[&str],
+) {
     let expected = std::fs::read_to_string(expected_path).unwrap();
 
     let dir = tempfile::tempdir().unwrap();
@@ -12,9 +20,10 @@ pub fn assert_compiled_output_eq(source_path: &str, expected_path: &str) {
 
     Command::new("./target/debug/silica")
         .arg("-i")
-        .arg(&source_path)
+        .arg(source_path)
         .arg("-o")
         .arg(&tmppath)
+        .args(flags)
         .output()
         .unwrap();
 
@@ -37,7 +46,7 @@ pub fn assert_compiler_passed(source_path: &str) {
 
     let output = Command::new("./target/debug/silica")
         .arg("-i")
-        .arg(&source_path)
+        .arg(source_path)
         .arg("-o")
         .arg(&tmppath)
         .output()